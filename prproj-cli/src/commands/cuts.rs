@@ -0,0 +1,42 @@
+use std::path::Path;
+
+use prproj::export::{self, clip_name};
+use serde_json::Value;
+
+use crate::output::{print_json, seconds, Table};
+use crate::project;
+
+pub fn run(path: &Path, sequence: &str, flatten: bool, json: bool) -> Result<(), String> {
+	let project = project::open(path, flatten)?.project;
+	let seq = project::find_sequence(&project, sequence)?;
+	let mut cuts: Vec<_> = seq.cuts().iter().collect();
+	cuts.sort_by(|a, b| {
+		(a.kind as u8, a.track)
+			.cmp(&(b.kind as u8, b.track))
+			.then(a.start.partial_cmp(&b.start).unwrap())
+	});
+
+	if json {
+		print_json(&Value::Array(
+			cuts
+				.iter()
+				.map(|cut| export::json::cut(&project, cut))
+				.collect()
+		));
+		return Ok(());
+	}
+	let mut table = Table::new(&["ID", "TRACK", "START", "END", "IN", "OUT", "SOURCE"]);
+	for cut in cuts {
+		table.row(vec![
+			cut.id.to_string(),
+			cut.kind.track_name(cut.track),
+			seq.timecode_at(cut.start).to_string(),
+			seq.timecode_at(cut.end).to_string(),
+			seconds(cut.in_point),
+			seconds(cut.out_point),
+			clip_name(&project, cut),
+		]);
+	}
+	table.print();
+	Ok(())
+}
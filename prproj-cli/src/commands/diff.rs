@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use prproj::diff::diff;
+
+use crate::output::print_json;
+use crate::project;
+
+pub fn run(old: &Path, new: &Path, json: bool) -> Result<(), String> {
+	let changes = diff(&project::open(old, false)?.project, &project::open(new, false)?.project);
+
+	if json {
+		print_json(&serde_json::to_value(&changes).map_err(|err| err.to_string())?);
+		return Ok(());
+	}
+	if changes.is_empty() {
+		println!("No changes");
+	}
+	for change in changes {
+		println!("{}", change);
+	}
+	Ok(())
+}
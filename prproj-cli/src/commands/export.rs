@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+use prproj::export::{csv, edl, fcp7, ffmpeg, json, otio};
+
+use crate::project;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+	/// CMX 3600 edit decision list
+	Edl,
+	/// Final Cut Pro 7 XML
+	Fcp7,
+	/// OpenTimelineIO
+	Otio,
+	/// One row per clip
+	Csv,
+	/// Sequence settings, clips and transitions
+	Json,
+	/// FFmpeg concat demuxer list
+	Ffconcat,
+}
+
+pub fn run(
+	path: &Path,
+	sequence: &str,
+	format: Format,
+	output: Option<&Path>,
+	flatten: bool,
+) -> Result<(), String> {
+	let project = project::open(path, flatten)?.project;
+	let seq = project::find_sequence(&project, sequence)?;
+	let exported = match format {
+		Format::Edl => edl::write(&project, seq),
+		Format::Fcp7 => fcp7::write(&project, seq),
+		Format::Otio => otio::write(&project, seq),
+		Format::Csv => csv::write(&project, seq),
+		Format::Json => json::write(&project, seq),
+		Format::Ffconcat => ffmpeg::concat_list(&project, seq).map_err(|err| err.to_string())?,
+	};
+	match output {
+		Some(output) => std::fs::write(output, exported)
+			.map_err(|err| format!("can't write {}: {}", output.display(), err)),
+		None => {
+			print!("{}", exported);
+			Ok(())
+		}
+	}
+}
@@ -0,0 +1,37 @@
+use std::path::Path;
+
+use prproj::fingerprint::fingerprint;
+use serde_json::{json, Value};
+
+use crate::output::{print_json, Table};
+use crate::project;
+
+pub fn run(path: &Path, sequence: Option<&str>, json: bool) -> Result<(), String> {
+	let project = project::open(path, false)?.project;
+	let sequences = match sequence {
+		Some(id_or_name) => vec![project::find_sequence(&project, id_or_name)?],
+		None => project.sequences.iter().collect(),
+	};
+
+	if json {
+		print_json(&Value::Array(sequences
+			.iter()
+			.map(|seq| json!({
+				"id": seq.id,
+				"name": seq.name(),
+				"fingerprint": fingerprint(&project, seq).to_string(),
+			}))
+			.collect()));
+		return Ok(());
+	}
+	let mut table = Table::new(&["ID", "NAME", "FINGERPRINT"]);
+	for seq in sequences {
+		table.row(vec![
+			seq.id.to_string(),
+			seq.name().to_owned(),
+			fingerprint(&project, seq).to_string(),
+		]);
+	}
+	table.print();
+	Ok(())
+}
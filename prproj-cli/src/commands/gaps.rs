@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use prproj::gaps::{flash_frames, gaps};
+use serde_json::json;
+
+use crate::output::{print_json, Table};
+use crate::project;
+
+pub fn run(path: &Path, id_or_name: &str, min_frames: u32, json: bool) -> Result<(), String> {
+	let project = project::open(path, false)?.project;
+	let seq = project::find_sequence(&project, id_or_name)?;
+	let gaps = gaps(seq);
+	let flash_frames = flash_frames(seq, min_frames);
+
+	if json {
+		print_json(&json!({
+			"gaps": gaps,
+			"flash_frames": flash_frames,
+		}));
+		return Ok(());
+	}
+	let mut table = Table::new(&["KIND", "TRACK", "START", "END", "FRAMES"]);
+	for gap in gaps.iter() {
+		table.row(vec![
+			String::from("gap"),
+			String::new(),
+			gap.start_timecode.to_string(),
+			gap.end_timecode.to_string(),
+			gap.frames.to_string(),
+		]);
+	}
+	for flash_frame in flash_frames.iter() {
+		let position = &flash_frame.position;
+		table.row(vec![
+			String::from("flash frame"),
+			position.track.to_owned(),
+			position.start_timecode.to_string(),
+			position.end_timecode.to_string(),
+			flash_frame.frames.to_string(),
+		]);
+	}
+	table.print();
+	Ok(())
+}
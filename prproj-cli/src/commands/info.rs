@@ -0,0 +1,28 @@
+use std::path::Path;
+
+use serde_json::json;
+
+use crate::output::print_json;
+use crate::project;
+
+pub fn run(path: &Path, json: bool) -> Result<(), String> {
+	let project::Opened { project, compressed } = project::open(path, false)?;
+	let cuts: usize = project.sequences.iter().map(|seq| seq.cuts().len()).sum();
+
+	if json {
+		print_json(&json!({
+			"path": path.display().to_string(),
+			"compressed": compressed,
+			"sequences": project.sequences.len(),
+			"media": project.media.len(),
+			"cuts": cuts,
+		}));
+	} else {
+		println!("Project:    {}", path.display());
+		println!("Compressed: {}", if compressed { "yes" } else { "no" });
+		println!("Sequences:  {}", project.sequences.len());
+		println!("Media:      {}", project.media.len());
+		println!("Clips:      {}", cuts);
+	}
+	Ok(())
+}
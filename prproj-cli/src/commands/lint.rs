@@ -0,0 +1,37 @@
+use std::path::Path;
+
+use prproj::lint::{lint, LintConfig, Rule, Severity};
+
+use crate::output::print_json;
+use crate::project;
+
+/// Prints the issues found, fails when some of them are errors.
+pub fn run(path: &Path, config: &LintConfig, json: bool) -> Result<(), String> {
+	let issues = lint(&project::open(path, false)?.project, config);
+
+	if json {
+		print_json(&serde_json::to_value(&issues).map_err(|err| err.to_string())?);
+	} else {
+		if issues.is_empty() {
+			println!("No issues");
+		}
+		for issue in issues.iter() {
+			println!("{}", issue);
+		}
+	}
+	match issues.iter().filter(|issue| issue.severity == Severity::Error).count() {
+		0 => Ok(()),
+		1 => Err(String::from("1 error")),
+		errors => Err(format!("{} errors", errors)),
+	}
+}
+
+/// `rules` to check instead of all of them, without those in `skip`.
+pub fn config(rules: &[Rule], skip: &[Rule], flash_frames: u32, final_sequences: &str) -> LintConfig {
+	let rules = if rules.is_empty() { &Rule::ALL[..] } else { rules };
+	LintConfig {
+		rules: rules.iter().copied().filter(|rule| !skip.contains(rule)).collect(),
+		flash_frames,
+		final_sequences: final_sequences.to_owned(),
+	}
+}
@@ -0,0 +1,40 @@
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::output::{print_json, Table};
+use crate::project;
+
+pub fn run(path: &Path, json: bool) -> Result<(), String> {
+	let project = project::open(path, false)?.project;
+	let mut media: Vec<_> = project.media.iter().collect();
+	media.sort_by(|a, b| a.file_path().cmp(b.file_path()));
+
+	if json {
+		print_json(&Value::Array(
+			media
+				.iter()
+				.map(|medium| json!({
+					"name": medium.file_name(),
+					"path": medium.file_path(),
+					"fps": prproj::timecode::fps(medium.frame_rate),
+					"width": medium.size.width,
+					"height": medium.size.height,
+					"duration": medium.duration().as_secs_f64(),
+				}))
+				.collect()
+		));
+		return Ok(());
+	}
+	let mut table = Table::new(&["NAME", "FPS", "DURATION", "PATH"]);
+	for medium in media {
+		table.row(vec![
+			medium.file_name().to_owned(),
+			format!("{:.3}", prproj::timecode::fps(medium.frame_rate)),
+			format!("{:.3}", medium.duration().as_secs_f64()),
+			medium.file_path().to_owned(),
+		]);
+	}
+	table.print();
+	Ok(())
+}
@@ -0,0 +1,44 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use prproj::merge::merge;
+use serde_json::json;
+
+use crate::output::print_json;
+
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+	std::fs::read(path).map_err(|err| format!("can't read {}: {}", path.display(), err))
+}
+
+/// Writes the merge of `ours` and `theirs` to `output`,
+/// fails after writing it when there are conflicts.
+pub fn run(base: &Path, ours: &Path, theirs: &Path, output: &Path, json: bool) -> Result<(), String> {
+	let merged = merge(&read(base)?, &read(ours)?, &read(theirs)?)
+		.map_err(|err| format!("can't merge: {}", err))?;
+
+	let file = File::create(output)
+		.map_err(|err| format!("can't write {}: {}", output.display(), err))?;
+	merged
+		.write_gzip(BufWriter::new(file))
+		.map_err(|err| format!("can't write {}: {}", output.display(), err))?;
+
+	if json {
+		print_json(&json!({
+			"applied": merged.applied,
+			"conflicts": merged.conflicts,
+		}));
+	} else {
+		for change in merged.applied.iter() {
+			println!("{}", change);
+		}
+		for conflict in merged.conflicts.iter() {
+			print!("{}", conflict);
+		}
+	}
+	match merged.conflicts.len() {
+		0 => Ok(()),
+		1 => Err(String::from("1 conflict, our version was kept")),
+		conflicts => Err(format!("{} conflicts, our versions were kept", conflicts)),
+	}
+}
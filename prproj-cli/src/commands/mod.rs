@@ -0,0 +1,12 @@
+pub mod cuts;
+pub mod diff;
+pub mod export;
+pub mod fingerprint;
+pub mod gaps;
+pub mod info;
+pub mod lint;
+pub mod media;
+pub mod merge;
+pub mod scan;
+pub mod sequences;
+pub mod tree;
@@ -0,0 +1,89 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use clap::ValueEnum;
+use prproj::scan::{self, ProjectIndex};
+use rusqlite::{params, Connection};
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+	/// A JSON object per project and line
+	Jsonl,
+	/// `projects`, `sequences` and `media` tables
+	Sqlite,
+}
+
+/// Replaces the tables of an earlier scan. Which projects use a clip:
+/// `SELECT DISTINCT projects.path FROM media
+/// JOIN sequences ON sequences.id = media.sequence
+/// JOIN projects ON projects.id = sequences.project
+/// WHERE media.path LIKE '%clip.mp4'`
+const SCHEMA: &str = "
+	DROP TABLE IF EXISTS media;
+	DROP TABLE IF EXISTS sequences;
+	DROP TABLE IF EXISTS projects;
+	CREATE TABLE projects (
+		id INTEGER PRIMARY KEY,
+		path TEXT NOT NULL,
+		error TEXT
+	);
+	CREATE TABLE sequences (
+		id INTEGER PRIMARY KEY,
+		project INTEGER NOT NULL REFERENCES projects (id),
+		sequence_id INTEGER NOT NULL,
+		name TEXT NOT NULL
+	);
+	CREATE TABLE media (
+		sequence INTEGER NOT NULL REFERENCES sequences (id),
+		path TEXT NOT NULL
+	);
+	CREATE INDEX media_path ON media (path);
+";
+
+pub fn run(dir: &Path, format: Format, output: Option<&Path>) -> Result<(), String> {
+	let projects = scan::scan(dir).map_err(|err| format!("can't scan {}: {}", dir.display(), err))?;
+	match (format, output) {
+		(Format::Jsonl, Some(output)) => File::create(output)
+			.and_then(|file| write_json_lines(&projects, BufWriter::new(file)))
+			.map_err(|err| format!("can't write {}: {}", output.display(), err))?,
+		(Format::Jsonl, None) => write_json_lines(&projects, io::stdout().lock())
+			.map_err(|err| format!("can't write the index: {}", err))?,
+		(Format::Sqlite, Some(output)) => write_sqlite(&projects, output)
+			.map_err(|err| format!("can't write {}: {}", output.display(), err))?,
+		(Format::Sqlite, None) => return Err(String::from("--output is required for SQLite")),
+	}
+	let failed = projects.iter().filter(|project| project.error.is_some()).count();
+	eprintln!("indexed {} projects, {} couldn't be read", projects.len(), failed);
+	Ok(())
+}
+
+fn write_json_lines<W: Write>(projects: &[ProjectIndex], mut out: W) -> io::Result<()> {
+	for project in projects {
+		serde_json::to_writer(&mut out, project)?;
+		writeln!(out)?;
+	}
+	out.flush()
+}
+
+fn write_sqlite(projects: &[ProjectIndex], output: &Path) -> rusqlite::Result<()> {
+	let mut db = Connection::open(output)?;
+	db.execute_batch(SCHEMA)?;
+	let transaction = db.transaction()?;
+	{
+		let mut insert_project = transaction.prepare("INSERT INTO projects (path, error) VALUES (?1, ?2)")?;
+		let mut insert_sequence = transaction
+			.prepare("INSERT INTO sequences (project, sequence_id, name) VALUES (?1, ?2, ?3)")?;
+		let mut insert_medium = transaction.prepare("INSERT INTO media (sequence, path) VALUES (?1, ?2)")?;
+		for project in projects {
+			let project_id = insert_project.insert(params![project.path.to_string_lossy(), project.error])?;
+			for seq in project.sequences.iter() {
+				let sequence_id = insert_sequence.insert(params![project_id, seq.id, seq.name])?;
+				for path in seq.media.iter() {
+					insert_medium.execute(params![sequence_id, path])?;
+				}
+			}
+		}
+	}
+	transaction.commit()
+}
@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use prproj::PremiereSequence;
+use serde_json::{json, Value};
+
+use crate::output::{print_json, Table};
+use crate::project;
+
+pub fn run(path: &Path, json: bool) -> Result<(), String> {
+	let project = project::open(path, false)?.project;
+
+	if json {
+		print_json(&Value::Array(project.sequences.iter().map(to_json).collect()));
+		return Ok(());
+	}
+	let mut table = Table::new(&["ID", "NAME", "SIZE", "FPS", "START", "DURATION", "CLIPS"]);
+	for seq in project.sequences.iter() {
+		let settings = seq.settings();
+		table.row(vec![
+			seq.id.to_string(),
+			seq.name().to_owned(),
+			format!("{}x{}", seq.size.width, seq.size.height),
+			format!("{:.3}", settings.fps()),
+			seq.start_timecode().to_string(),
+			seq.timecode_at(seq.end()).to_string(),
+			seq.cuts().len().to_string(),
+		]);
+	}
+	table.print();
+	Ok(())
+}
+
+fn to_json(seq: &PremiereSequence) -> Value {
+	let settings = seq.settings();
+	json!({
+		"id": seq.id,
+		"name": seq.name(),
+		"width": seq.size.width,
+		"height": seq.size.height,
+		"fps": settings.fps(),
+		"start_timecode": seq.start_timecode().to_string(),
+		"duration": seq.end(),
+		"work_area": [seq.work_area().0, seq.work_area().1],
+		"clips": seq.cuts().len(),
+	})
+}
@@ -0,0 +1,81 @@
+use std::path::Path;
+
+use prproj::export::clip_name;
+use prproj::premiere::{Cut, TrackKind};
+use prproj::PremiereSequence;
+use serde_json::{json, Value};
+
+use crate::output::print_json;
+use crate::project;
+
+pub fn run(path: &Path, json: bool) -> Result<(), String> {
+	let project = project::open(path, false)?.project;
+
+	if json {
+		print_json(&Value::Array(
+			project.sequences
+				.iter()
+				.map(|seq| json!({
+					"id": seq.id,
+					"name": seq.name(),
+					"tracks": tracks(seq)
+						.into_iter()
+						.map(|(kind, track, cuts)| json!({
+							"track": kind.track_name(track),
+							"clips": cuts
+								.iter()
+								.map(|cut| clip_name(&project, cut))
+								.collect::<Vec<_>>(),
+						}))
+						.collect::<Vec<_>>(),
+				}))
+				.collect()
+		));
+		return Ok(());
+	}
+	println!("{}", path.display());
+	for (seq_index, seq) in project.sequences.iter().enumerate() {
+		let last_seq = seq_index + 1 == project.sequences.len();
+		println!("{}{} ({})", branch(last_seq), seq.name(), seq.id);
+		let tracks = tracks(seq);
+		for (track_index, (kind, track, cuts)) in tracks.iter().enumerate() {
+			let last_track = track_index + 1 == tracks.len();
+			println!("{}{}{}", indent(last_seq), branch(last_track), kind.track_name(*track));
+			for (cut_index, cut) in cuts.iter().enumerate() {
+				println!(
+					"{}{}{}{} {}",
+					indent(last_seq),
+					indent(last_track),
+					branch(cut_index + 1 == cuts.len()),
+					seq.timecode_at(cut.start),
+					clip_name(&project, cut)
+				);
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Tracks which have clips, video before audio, with their clips in order.
+fn tracks(seq: &PremiereSequence) -> Vec<(TrackKind, usize, Vec<&Cut>)> {
+	let mut tracks: Vec<(TrackKind, usize, Vec<&Cut>)> = Vec::new();
+	for cut in seq.cuts().iter() {
+		match tracks.iter_mut().find(|(kind, track, _)| *kind == cut.kind && *track == cut.track) {
+			Some((_, _, cuts)) => cuts.push(cut),
+			None => tracks.push((cut.kind, cut.track, vec![cut])),
+		}
+	}
+	for (_, _, cuts) in tracks.iter_mut() {
+		cuts.sort_by(|a, b| a.start.partial_cmp(&b.start).unwrap());
+	}
+	tracks.sort_by_key(|(kind, track, _)| (*kind == TrackKind::Audio, *track));
+	tracks
+}
+
+fn branch(last: bool) -> &'static str {
+	if last { "└── " } else { "├── " }
+}
+
+fn indent(last: bool) -> &'static str {
+	if last { "    " } else { "│   " }
+}
@@ -0,0 +1,152 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use prproj::lint::Rule;
+
+mod commands;
+mod output;
+mod project;
+
+/// Inspects Adobe Premiere Pro projects (`.prproj`, gzipped or not).
+#[derive(Parser)]
+#[command(name = "prproj", version)]
+struct Cli {
+	/// Print JSON instead of tables
+	#[arg(long, global = true)]
+	json: bool,
+
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// Summary of a project
+	Info {
+		project: PathBuf,
+	},
+	/// Lists the sequences of a project
+	Sequences {
+		project: PathBuf,
+	},
+	/// Lists the media used in sequences
+	Media {
+		project: PathBuf,
+	},
+	/// Shows the sequences with their tracks and clips as a tree
+	Tree {
+		project: PathBuf,
+	},
+	/// Lists the clips of a sequence
+	Cuts {
+		project: PathBuf,
+		/// ID or name of the sequence
+		sequence: String,
+		/// Replace nested sequences with the clips they consist of
+		#[arg(long)]
+		flatten: bool,
+	},
+	/// Converts a sequence to an interchange format
+	Export {
+		project: PathBuf,
+		/// ID or name of the sequence
+		#[arg(long, short)]
+		sequence: String,
+		#[arg(long, short, value_enum)]
+		format: commands::export::Format,
+		/// File to write to instead of the standard output
+		#[arg(long, short)]
+		output: Option<PathBuf>,
+		/// Replace nested sequences with the clips they consist of
+		#[arg(long)]
+		flatten: bool,
+	},
+	/// Lists what changed between two versions of a project
+	Diff {
+		old: PathBuf,
+		new: PathBuf,
+	},
+	/// Merges the changes of two copies of a project made since their common base
+	Merge {
+		base: PathBuf,
+		ours: PathBuf,
+		theirs: PathBuf,
+		/// Where to write the merged project
+		#[arg(long, short)]
+		output: PathBuf,
+	},
+	/// Prints fingerprints which change only when what a sequence plays back changes
+	Fingerprint {
+		project: PathBuf,
+		/// ID or name of the sequence, all sequences if left out
+		sequence: Option<String>,
+	},
+	/// Lists the gaps in the video and the clips shorter than a few frames
+	Gaps {
+		project: PathBuf,
+		/// ID or name of the sequence
+		sequence: String,
+		/// Clips shorter than this many frames are flash frames
+		#[arg(long, default_value_t = 2)]
+		min_frames: u32,
+	},
+	/// Checks for offline media, gaps, flash frames and other mistakes
+	Lint {
+		project: PathBuf,
+		/// Only check these rules, e.g. `gap` or `flash_frame`
+		#[arg(long = "rule", value_name = "RULE")]
+		rules: Vec<Rule>,
+		/// Don't check these rules
+		#[arg(long, value_name = "RULE")]
+		skip: Vec<Rule>,
+		/// Clips on V1 this many frames long or shorter are flash frames
+		#[arg(long, default_value_t = 1)]
+		flash_frames: u32,
+		/// Sequences with this in their name must not have muted tracks
+		#[arg(long = "final", default_value = "final", value_name = "NAME")]
+		final_sequences: String,
+	},
+	/// Indexes the projects below a directory with the media their sequences use
+	Scan {
+		dir: PathBuf,
+		#[arg(long, short, value_enum, default_value = "jsonl")]
+		format: commands::scan::Format,
+		/// File to write to instead of the standard output, required for SQLite
+		#[arg(long, short)]
+		output: Option<PathBuf>,
+	},
+}
+
+fn main() -> ExitCode {
+	let cli = Cli::parse();
+	let result = match &cli.command {
+		Command::Info { project } => commands::info::run(project, cli.json),
+		Command::Sequences { project } => commands::sequences::run(project, cli.json),
+		Command::Media { project } => commands::media::run(project, cli.json),
+		Command::Tree { project } => commands::tree::run(project, cli.json),
+		Command::Cuts { project, sequence, flatten } =>
+			commands::cuts::run(project, sequence, *flatten, cli.json),
+		Command::Export { project, sequence, format, output, flatten } =>
+			commands::export::run(project, sequence, *format, output.as_deref(), *flatten),
+		Command::Diff { old, new } => commands::diff::run(old, new, cli.json),
+		Command::Merge { base, ours, theirs, output } =>
+			commands::merge::run(base, ours, theirs, output, cli.json),
+		Command::Fingerprint { project, sequence } =>
+			commands::fingerprint::run(project, sequence.as_deref(), cli.json),
+		Command::Gaps { project, sequence, min_frames } =>
+			commands::gaps::run(project, sequence, *min_frames, cli.json),
+		Command::Lint { project, rules, skip, flash_frames, final_sequences } => {
+			let config = commands::lint::config(rules, skip, *flash_frames, final_sequences);
+			commands::lint::run(project, &config, cli.json)
+		}
+		Command::Scan { dir, format, output } => commands::scan::run(dir, *format, output.as_deref()),
+	};
+	match result {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(message) => {
+			eprintln!("error: {}", message);
+			ExitCode::FAILURE
+		}
+	}
+}
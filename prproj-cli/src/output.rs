@@ -0,0 +1,49 @@
+use serde_json::Value;
+
+/// A table printed with aligned columns.
+pub struct Table {
+	header: Vec<String>,
+	rows: Vec<Vec<String>>,
+}
+
+impl Table {
+	pub fn new(header: &[&str]) -> Self {
+		Self {
+			header: header.iter().map(|title| title.to_string()).collect(),
+			rows: Vec::new(),
+		}
+	}
+
+	pub fn row(&mut self, row: Vec<String>) {
+		self.rows.push(row);
+	}
+
+	pub fn print(&self) {
+		let mut widths: Vec<usize> = self.header.iter().map(|title| title.chars().count()).collect();
+		for row in self.rows.iter() {
+			for (width, cell) in widths.iter_mut().zip(row) {
+				*width = (*width).max(cell.chars().count());
+			}
+		}
+		let line = |cells: &[String]| {
+			let padded: Vec<String> = cells
+				.iter()
+				.zip(widths.iter())
+				.map(|(cell, width)| format!("{:<width$}", cell, width = width))
+				.collect();
+			println!("{}", padded.join("  ").trim_end());
+		};
+		line(&self.header);
+		for row in self.rows.iter() {
+			line(row);
+		}
+	}
+}
+
+pub fn print_json(value: &Value) {
+	println!("{}", serde_json::to_string_pretty(value).unwrap());
+}
+
+pub fn seconds(seconds: f64) -> String {
+	format!("{:.3}", seconds)
+}
@@ -0,0 +1,43 @@
+use std::fs::File;
+use std::path::Path;
+
+use prproj::{PremiereFile, PremiereReader, PremiereSequence, Reader};
+
+/// A project read by `open`.
+pub struct Opened {
+	pub project: PremiereFile,
+	/// Whether the file was gzipped.
+	pub compressed: bool,
+}
+
+/// Reads and parses a project file, `-` reads it from the standard input.
+pub fn open(path: &Path, flatten_nested: bool) -> Result<Opened, String> {
+	let reader = if path == Path::new("-") {
+		PremiereReader::from_reader(std::io::stdin().lock())
+	} else {
+		let file = File::open(path)
+			.map_err(|err| format!("can't read {}: {}", path.display(), err))?;
+		PremiereReader::from_reader(file)
+	};
+	let mut reader = reader
+		.map_err(|err| format!("can't parse {}: {}", path.display(), err))?;
+	reader.set_flatten_nested(flatten_nested);
+	reader
+		.read()
+		.map_err(|err| format!("can't parse {}: {}", path.display(), err))?;
+	Ok(Opened {
+		compressed: reader.is_compressed(),
+		project: reader.take(),
+	})
+}
+
+/// Finds a sequence by its ID or else by its name.
+pub fn find_sequence<'a>(project: &'a PremiereFile, id_or_name: &str) -> Result<&'a PremiereSequence, String> {
+	let by_id = id_or_name
+		.parse::<u32>()
+		.ok()
+		.and_then(|id| project.sequences.iter().find(|seq| seq.id == id));
+	by_id
+		.or_else(|| project.sequences.iter().find(|seq| seq.name() == id_or_name))
+		.ok_or_else(|| format!("no sequence with ID or name \"{}\"", id_or_name))
+}
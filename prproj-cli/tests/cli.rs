@@ -0,0 +1,241 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn test_file(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.pop();
+    path.push("test_files");
+    path.push(name);
+    path
+}
+
+/// A directory of its own for every run of the tests, emptied first.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn prproj(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_prproj"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn json(args: &[&str]) -> serde_json::Value {
+    let output = prproj(args);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn it_lists_sequences_as_json() {
+    let project = test_file("test.zipped.prproj");
+    let sequences = json(&["--json", "sequences", project.to_str().unwrap()]);
+    let names: Vec<_> = sequences
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|seq| seq["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["some_useless_sequence", "test_supercut"]);
+}
+
+#[test]
+fn it_finds_sequences_by_name_or_id() {
+    let project = test_file("test.unzipped.prproj");
+    let by_name = json(&["cuts", project.to_str().unwrap(), "test_supercut", "--json"]);
+    let by_id = json(&["cuts", project.to_str().unwrap(), "2", "--json"]);
+    assert_eq!(by_name, by_id);
+    assert_eq!(by_name.as_array().unwrap().len(), 18);
+}
+
+#[test]
+fn it_prints_tables() {
+    let project = test_file("test.unzipped.prproj");
+    let output = prproj(&["media", project.to_str().unwrap()]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("NAME"));
+    assert_eq!(stdout.lines().count(), 4);
+}
+
+#[test]
+fn it_fails_on_unknown_sequences() {
+    let project = test_file("test.unzipped.prproj");
+    let output = prproj(&["cuts", project.to_str().unwrap(), "missing"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no sequence"));
+}
+
+#[test]
+fn it_exports_sequences() {
+    let project = test_file("test.unzipped.prproj");
+    let otio = json(&["export", project.to_str().unwrap(), "-s", "test_supercut", "-f", "otio"]);
+    assert_eq!(otio["OTIO_SCHEMA"], "Timeline.1");
+    let tracks: Vec<_> = otio["tracks"]["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|track| track["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(tracks, ["V1", "V2", "A1", "A2"]);
+
+    let seq = json(&["export", project.to_str().unwrap(), "-s", "2", "-f", "json"]);
+    assert_eq!(seq["name"], "test_supercut");
+    assert_eq!(seq["clips"].as_array().unwrap().len(), 18);
+
+    let output = prproj(&["export", project.to_str().unwrap(), "-s", "missing", "-f", "edl"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn it_diffs_projects() {
+    let zipped = test_file("test.zipped.prproj");
+    let unzipped = test_file("test.unzipped.prproj");
+    let output = prproj(&["diff", zipped.to_str().unwrap(), unzipped.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "No changes\n");
+
+    let changes = json(&["diff", zipped.to_str().unwrap(), unzipped.to_str().unwrap(), "--json"]);
+    assert_eq!(changes, serde_json::json!([]));
+}
+
+#[test]
+fn it_merges_projects() {
+    let base = test_file("test.zipped.prproj");
+    let output = temp_dir("prproj-cli-merged").with_extension("prproj");
+    let result = prproj(&[
+        "merge",
+        base.to_str().unwrap(),
+        base.to_str().unwrap(),
+        base.to_str().unwrap(),
+        "-o",
+        output.to_str().unwrap(),
+    ]);
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+
+    let changes = json(&["diff", base.to_str().unwrap(), output.to_str().unwrap(), "--json"]);
+    assert_eq!(changes, serde_json::json!([]));
+    std::fs::remove_file(output).unwrap();
+}
+
+#[test]
+fn it_prints_fingerprints() {
+    let zipped = test_file("test.zipped.prproj");
+    let unzipped = test_file("test.unzipped.prproj");
+    let all = json(&["fingerprint", zipped.to_str().unwrap(), "--json"]);
+    assert_eq!(all.as_array().unwrap().len(), 2);
+
+    let one = json(&["fingerprint", unzipped.to_str().unwrap(), "test_supercut", "--json"]);
+    assert_eq!(one[0]["fingerprint"], all[1]["fingerprint"]);
+    assert_eq!(one[0]["fingerprint"].as_str().unwrap().len(), 16);
+}
+
+#[test]
+fn it_lints_projects() {
+    let project = test_file("test.unzipped.prproj");
+    // The gap at the start of V1 is filled by the clip on V2
+    let output = prproj(&["lint", project.to_str().unwrap(), "--skip", "offline-media"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "No issues\n");
+
+    let output = prproj(&["lint", project.to_str().unwrap(), "--skip", "offline-media", "--flash-frames", "80"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "error[flash_frame] \"test_supercut\" 00:00:44:00-00:00:47:04: clip of 76 frames on V1\n"
+    );
+
+    let issues = json(&["lint", project.to_str().unwrap(), "--rule", "flash_frame", "--json"]);
+    assert_eq!(issues, serde_json::json!([]));
+}
+
+#[test]
+fn it_lists_gaps() {
+    let project = test_file("test.unzipped.prproj");
+    let output = prproj(&["gaps", project.to_str().unwrap(), "test_supercut"]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "KIND  TRACK  START  END  FRAMES\n");
+
+    let found = json(&["gaps", project.to_str().unwrap(), "2", "--min-frames", "1000", "--json"]);
+    assert_eq!(found["gaps"], serde_json::json!([]));
+    assert_eq!(found["flash_frames"].as_array().unwrap().len(), 18);
+    assert_eq!(found["flash_frames"][0]["position"]["track"], "V1");
+}
+
+/// Runs `prproj` with the contents of a test file on its standard input.
+fn prproj_stdin(args: &[&str], name: &str) -> Output {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_prproj"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let project = std::fs::read(test_file(name)).unwrap();
+    child.stdin.take().unwrap().write_all(&project).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn it_reads_projects_from_stdin() {
+    let output = prproj_stdin(&["--json", "sequences", "-"], "test.zipped.prproj");
+    assert!(output.status.success());
+    let sequences: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(sequences.as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn it_tells_whether_projects_are_compressed() {
+    for (name, compressed) in [("test.zipped.prproj", true), ("test.unzipped.prproj", false)] {
+        let info = json(&["--json", "info", test_file(name).to_str().unwrap()]);
+        assert_eq!(info["compressed"], compressed, "{}", name);
+
+        let output = prproj_stdin(&["--json", "info", "-"], name);
+        assert!(output.status.success());
+        let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(info["compressed"], compressed, "{} from stdin", name);
+    }
+}
+
+#[test]
+fn it_scans_directories() {
+    let dir = temp_dir("prproj-cli-scan");
+    std::fs::create_dir_all(dir.join("archive")).unwrap();
+    std::fs::copy(test_file("test.zipped.prproj"), dir.join("archive").join("film.prproj")).unwrap();
+    std::fs::write(dir.join("broken.prproj"), "not a project").unwrap();
+
+    let output = prproj(&["scan", dir.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let lines: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0]["path"].as_str().unwrap().ends_with("film.prproj"));
+    assert_eq!(lines[0]["sequences"][1]["name"], "test_supercut");
+    assert!(lines[1]["error"].is_string());
+
+    let index = dir.join("index.sqlite");
+    let output = prproj(&["scan", dir.to_str().unwrap(), "-f", "sqlite", "-o", index.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let clip = lines[0]["sequences"][1]["media"][0].as_str().unwrap();
+    let db = rusqlite::Connection::open(&index).unwrap();
+    let users: Vec<String> = db
+        .prepare(
+            "SELECT DISTINCT projects.path FROM media \
+             JOIN sequences ON sequences.id = media.sequence \
+             JOIN projects ON projects.id = sequences.project \
+             WHERE media.path = ?1",
+        )
+        .unwrap()
+        .query_map([clip], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(users.len(), 1);
+    assert!(users[0].ends_with("film.prproj"));
+}
@@ -0,0 +1,50 @@
+//! Reading a project with the sequences resolved serially and in parallel.
+//!
+//! `cargo bench -p prproj --features rayon`
+//!
+//! The test project has only two sequences, set `PRPROJ_BENCH_PROJECT`
+//! to the path of a larger one to see a difference.
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use prproj::{PremiereReader, Reader};
+use std::path::PathBuf;
+
+fn project() -> Vec<u8> {
+	let path = match std::env::var_os("PRPROJ_BENCH_PROJECT") {
+		Some(path) => PathBuf::from(path),
+		None => {
+			let mut path = std::env::current_dir().unwrap();
+			path.pop();
+			path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+			path
+		}
+	};
+	std::fs::read(path).unwrap()
+}
+
+fn read(c: &mut Criterion) {
+	let xml = project();
+	let mut group = c.benchmark_group("read");
+	for (name, parallel) in [("serial", false), ("parallel", true)] {
+		for flatten in [false, true] {
+			let id = if flatten { format!("{}_flattened", name) } else { name.to_owned() };
+			// Only `read` is measured, not parsing the XML
+			group.bench_function(id, |b| b.iter_batched(
+				|| {
+					let mut reader = PremiereReader::from_reader(&xml[..]).unwrap();
+					reader.set_parallel(parallel);
+					reader.set_flatten_nested(flatten);
+					reader
+				},
+				|mut reader| {
+					reader.read().unwrap();
+					reader.take()
+				},
+				BatchSize::LargeInput,
+			));
+		}
+	}
+	group.finish();
+}
+
+criterion_group!(benches, read);
+criterion_main!(benches);
@@ -0,0 +1,55 @@
+//! Decompression of gzipped projects.
+//!
+//! Premiere saves projects gzipped. `PremiereReader` recognizes them by
+//! their magic bytes and hands them to a `Decompress` implementation:
+//! natively `Libflate`, which inflates while the XML is being parsed.
+//! In the browser a JavaScript inflate, or the output of
+//! `DecompressionStream` collected beforehand, can be plugged in with `Callback`.
+use std::io::{self, Cursor, Read};
+
+use libflate::gzip;
+
+/// First two bytes of every gzip file.
+pub const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+pub fn is_gzip(bytes: &[u8]) -> bool {
+	bytes.starts_with(&GZIP_MAGIC)
+}
+
+pub trait Decompress {
+	/// Turns a gzip stream into a stream of the XML it contains.
+	fn decompress<'a>(&self, gzip: Box<dyn Read + 'a>) -> io::Result<Box<dyn Read + 'a>>;
+}
+
+/// Streaming gzip decoder, nothing is buffered besides its window.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Libflate;
+
+impl Decompress for Libflate {
+	fn decompress<'a>(&self, gzip: Box<dyn Read + 'a>) -> io::Result<Box<dyn Read + 'a>> {
+		Ok(Box::new(gzip::Decoder::new(gzip)?))
+	}
+}
+
+/// Decompresses the whole file at once with a function,
+/// e.g. one calling into JavaScript.
+/// ```
+/// # use prproj::decompress::Callback;
+/// # use prproj::PremiereReader;
+/// let inflate = Callback(|gzip: &[u8]| -> Result<Vec<u8>, String> {
+///     Err(format!("can't inflate {} bytes", gzip.len()))
+/// });
+/// assert!(PremiereReader::with_decompressor(&[0x1f, 0x8b, 0], &inflate).is_err());
+/// ```
+pub struct Callback<F>(pub F);
+
+impl<F> Decompress for Callback<F>
+	where F: Fn(&[u8]) -> Result<Vec<u8>, String>
+{
+	fn decompress<'a>(&self, mut gzip: Box<dyn Read + 'a>) -> io::Result<Box<dyn Read + 'a>> {
+		let mut compressed = Vec::new();
+		gzip.read_to_end(&mut compressed)?;
+		let xml = (self.0)(&compressed).map_err(io::Error::other)?;
+		Ok(Box::new(Cursor::new(xml)))
+	}
+}
@@ -0,0 +1,474 @@
+//! Structural comparison of two versions of a project.
+//!
+//! Sequences, media and Project panel items are matched by their `ObjectUID`.
+//! Clips on a timeline have no such identity, so a cut is matched with a cut
+//! of the same kind playing the same source, preferring an identical one and
+//! otherwise the one sharing most of its source range.
+//! Markers are matched by their GUID, markers of clips are compared for
+//! the matched cuts showing them.
+use std::fmt;
+
+use crate::premiere::{Cut, CutPosition, CutSource, Marker, PremiereMedium, PremiereSequence, ProjectItem};
+use crate::timecode::Timecode;
+use crate::PremiereFile;
+
+/// Times closer than this are considered equal.
+const EPSILON: f64 = 1e-6;
+
+/// A single difference between two versions of a project.
+/// `sequence` fields hold the name of the sequence in the newer version.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "change", rename_all = "snake_case"))]
+pub enum Change {
+	MediumAdded { uid: String, path: String },
+	MediumRemoved { uid: String, path: String },
+	/// The file of a medium was replaced, e.g. with *Link Media* or *Replace Footage*.
+	MediumRelinked { uid: String, old_path: String, new_path: String },
+	ItemAdded { uid: String, name: String, bin: String },
+	ItemRemoved { uid: String, name: String, bin: String },
+	/// A bin or item was dragged into another bin.
+	ItemMoved { uid: String, name: String, old_bin: String, new_bin: String },
+	ItemRenamed { uid: String, old_name: String, new_name: String },
+	SequenceAdded { uid: String, name: String },
+	SequenceRemoved { uid: String, name: String },
+	SequenceRenamed { uid: String, old_name: String, new_name: String },
+	SettingChanged { sequence: String, setting: String, old: String, new: String },
+	/// The in or out mark or an end of the work area was set, moved or cleared.
+	MarkChanged { sequence: String, mark: String, old: Option<Timecode>, new: Option<Timecode> },
+	CutAdded { sequence: String, source: String, cut: CutPosition },
+	CutRemoved { sequence: String, source: String, cut: CutPosition },
+	/// Same part of the source, somewhere else on the timeline.
+	CutMoved { sequence: String, source: String, old: CutPosition, new: CutPosition },
+	/// The in or out point changed, the cut may have moved as well.
+	CutTrimmed { sequence: String, source: String, old: CutPosition, new: CutPosition },
+	/// `cut` is the clip the marker is on, `None` for markers of the sequence.
+	/// `at` is where the marker lies on the timeline.
+	MarkerAdded { sequence: String, cut: Option<CutPosition>, at: Timecode, marker: Marker },
+	MarkerRemoved { sequence: String, cut: Option<CutPosition>, at: Timecode, marker: Marker },
+	/// The marker was renamed, commented, moved, resized or recolored.
+	MarkerChanged { sequence: String, cut: Option<CutPosition>, at: Timecode, old: Marker, new: Marker },
+}
+
+impl fmt::Display for Change {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let timecode = |timecode: &Option<Timecode>| match timecode {
+			Some(timecode) => timecode.to_string(),
+			None => String::from("none")
+		};
+		let on_cut = |cut: &Option<CutPosition>| match cut {
+			Some(cut) => format!("{} ", cut),
+			None => String::new()
+		};
+		match self {
+			Change::MediumAdded { path, .. } => write!(f, "+ medium {}", path),
+			Change::MediumRemoved { path, .. } => write!(f, "- medium {}", path),
+			Change::MediumRelinked { old_path, new_path, .. } =>
+				write!(f, "~ medium relinked {} -> {}", old_path, new_path),
+			Change::ItemAdded { name, bin, .. } => write!(f, "+ item \"{}\" in /{}", name, bin),
+			Change::ItemRemoved { name, bin, .. } => write!(f, "- item \"{}\" in /{}", name, bin),
+			Change::ItemMoved { name, old_bin, new_bin, .. } =>
+				write!(f, "~ item \"{}\" moved /{} -> /{}", name, old_bin, new_bin),
+			Change::ItemRenamed { old_name, new_name, .. } =>
+				write!(f, "~ item \"{}\" renamed to \"{}\"", old_name, new_name),
+			Change::SequenceAdded { name, .. } => write!(f, "+ sequence \"{}\"", name),
+			Change::SequenceRemoved { name, .. } => write!(f, "- sequence \"{}\"", name),
+			Change::SequenceRenamed { old_name, new_name, .. } =>
+				write!(f, "~ sequence \"{}\" renamed to \"{}\"", old_name, new_name),
+			Change::SettingChanged { sequence, setting, old, new } =>
+				write!(f, "~ \"{}\" {} {} -> {}", sequence, setting, old, new),
+			Change::MarkChanged { sequence, mark, old, new } =>
+				write!(f, "~ \"{}\" {} {} -> {}", sequence, mark, timecode(old), timecode(new)),
+			Change::CutAdded { sequence, source, cut } =>
+				write!(f, "+ \"{}\" {} {}", sequence, cut, source),
+			Change::CutRemoved { sequence, source, cut } =>
+				write!(f, "- \"{}\" {} {}", sequence, cut, source),
+			Change::CutMoved { sequence, source, old, new } =>
+				write!(f, "~ \"{}\" {} moved {} -> {}", sequence, source, old, new),
+			Change::CutTrimmed { sequence, source, old, new } =>
+				write!(f, "~ \"{}\" {} trimmed {} -> {}", sequence, source, old, new),
+			Change::MarkerAdded { sequence, cut, at, marker } =>
+				write!(f, "+ \"{}\" {}marker \"{}\" at {}", sequence, on_cut(cut), marker.name, at),
+			Change::MarkerRemoved { sequence, cut, at, marker } =>
+				write!(f, "- \"{}\" {}marker \"{}\" at {}", sequence, on_cut(cut), marker.name, at),
+			Change::MarkerChanged { sequence, cut, at, old, .. } =>
+				write!(f, "~ \"{}\" {}marker \"{}\" changed at {}", sequence, on_cut(cut), old.name, at),
+		}
+	}
+}
+
+/// Lists what changed from `old` to `new`: media first, then
+/// the Project panel, then every sequence with its cuts in timeline order.
+pub fn diff(old: &PremiereFile, new: &PremiereFile) -> Vec<Change> {
+	let mut changes = Vec::new();
+	diff_media(&old.media, &new.media, &mut changes);
+	diff_items(&old.items, &new.items, &mut changes);
+	for old_seq in old.sequences.iter() {
+		if !new.sequences.iter().any(|new_seq| same_sequence(old_seq, new_seq)) {
+			changes.push(Change::SequenceRemoved {
+				uid: old_seq.uid.to_owned(),
+				name: old_seq.name.to_owned(),
+			});
+		}
+	}
+	for new_seq in new.sequences.iter() {
+		match old.sequences.iter().find(|old_seq| same_sequence(old_seq, new_seq)) {
+			Some(old_seq) => diff_sequence(old, old_seq, new, new_seq, &mut changes),
+			None => changes.push(Change::SequenceAdded {
+				uid: new_seq.uid.to_owned(),
+				name: new_seq.name.to_owned(),
+			}),
+		}
+	}
+	changes
+}
+
+fn same_sequence(old: &PremiereSequence, new: &PremiereSequence) -> bool {
+	if old.uid.is_empty() || new.uid.is_empty() {
+		old.name == new.name
+	} else {
+		old.uid == new.uid
+	}
+}
+
+fn medium_key(medium: &PremiereMedium) -> &str {
+	if medium.uid().is_empty() {
+		medium.file_path()
+	} else {
+		medium.uid()
+	}
+}
+
+fn diff_media(old: &[PremiereMedium], new: &[PremiereMedium], changes: &mut Vec<Change>) {
+	for old_medium in old {
+		match new.iter().find(|new_medium| medium_key(new_medium) == medium_key(old_medium)) {
+			Some(new_medium) if new_medium.file_path() != old_medium.file_path() =>
+				changes.push(Change::MediumRelinked {
+					uid: old_medium.uid().to_owned(),
+					old_path: old_medium.file_path().to_owned(),
+					new_path: new_medium.file_path().to_owned(),
+				}),
+			Some(_) => {}
+			None => changes.push(Change::MediumRemoved {
+				uid: old_medium.uid().to_owned(),
+				path: old_medium.file_path().to_owned(),
+			}),
+		}
+	}
+	for new_medium in new {
+		if !old.iter().any(|old_medium| medium_key(old_medium) == medium_key(new_medium)) {
+			changes.push(Change::MediumAdded {
+				uid: new_medium.uid().to_owned(),
+				path: new_medium.file_path().to_owned(),
+			});
+		}
+	}
+}
+
+fn diff_items(old: &[ProjectItem], new: &[ProjectItem], changes: &mut Vec<Change>) {
+	for old_item in old {
+		match new.iter().find(|new_item| new_item.uid == old_item.uid) {
+			Some(new_item) => {
+				if new_item.name != old_item.name {
+					changes.push(Change::ItemRenamed {
+						uid: new_item.uid.to_owned(),
+						old_name: old_item.name.to_owned(),
+						new_name: new_item.name.to_owned(),
+					});
+				}
+				// Contents of a renamed bin aren't reported as moved
+				let old_bins = old_item.bin.iter().map(|bin| bin_uid(old, bin));
+				let new_bins = new_item.bin.iter().map(|bin| bin_uid(new, bin));
+				if !old_bins.eq(new_bins) {
+					changes.push(Change::ItemMoved {
+						uid: new_item.uid.to_owned(),
+						name: new_item.name.to_owned(),
+						old_bin: old_item.bin_path(),
+						new_bin: new_item.bin_path(),
+					});
+				}
+			}
+			None => changes.push(Change::ItemRemoved {
+				uid: old_item.uid.to_owned(),
+				name: old_item.name.to_owned(),
+				bin: old_item.bin_path(),
+			}),
+		}
+	}
+	for new_item in new {
+		if !old.iter().any(|old_item| old_item.uid == new_item.uid) {
+			changes.push(Change::ItemAdded {
+				uid: new_item.uid.to_owned(),
+				name: new_item.name.to_owned(),
+				bin: new_item.bin_path(),
+			});
+		}
+	}
+}
+
+/// `ObjectUID` of the bin called `name`, falls back to the name.
+fn bin_uid<'a>(items: &'a [ProjectItem], name: &'a str) -> &'a str {
+	items
+		.iter()
+		.find(|item| item.is_bin && item.name == name)
+		.map_or(name, |item| item.uid.as_str())
+}
+
+pub(crate) fn diff_sequence(
+	old_file: &PremiereFile,
+	old: &PremiereSequence,
+	new_file: &PremiereFile,
+	new: &PremiereSequence,
+	changes: &mut Vec<Change>,
+) {
+	let sequence = new.name.to_owned();
+	if old.name != new.name {
+		changes.push(Change::SequenceRenamed {
+			uid: new.uid.to_owned(),
+			old_name: old.name.to_owned(),
+			new_name: new.name.to_owned(),
+		});
+	}
+
+	let settings = |seq: &PremiereSequence| {
+		let settings = seq.settings();
+		vec![
+			("frame size", format!("{}x{}", settings.frame_size.width, settings.frame_size.height)),
+			("frame rate", format!("{}", (settings.fps() * 1000.).round() / 1000.)),
+			("pixel aspect ratio", format!("{}:{}", settings.pixel_aspect_ratio.0, settings.pixel_aspect_ratio.1)),
+			("field order", format!("{:?}", settings.field_order)),
+			("drop frame", settings.drop_frame.to_string()),
+			("audio sample rate", settings.audio_sample_rate.to_string()),
+			("audio channels", format!("{:?}", settings.audio_channels)),
+			("start timecode", seq.start_timecode().to_string()),
+		]
+	};
+	for ((setting, old_value), (_, new_value)) in settings(old).into_iter().zip(settings(new)) {
+		if old_value != new_value {
+			changes.push(Change::SettingChanged {
+				sequence: sequence.to_owned(),
+				setting: setting.to_owned(),
+				old: old_value,
+				new: new_value,
+			});
+		}
+	}
+
+	let marks = |seq: &PremiereSequence| vec![
+		("in mark", seq.in_mark()),
+		("out mark", seq.out_mark()),
+		("work area start", Some(seq.work_area().0)),
+		("work area end", Some(seq.work_area().1)),
+	];
+	for ((mark, old_mark), (_, new_mark)) in marks(old).into_iter().zip(marks(new)) {
+		let old_mark = old_mark.map(|time| old.timecode_at(time));
+		let new_mark = new_mark.map(|time| new.timecode_at(time));
+		if old_mark != new_mark {
+			changes.push(Change::MarkChanged {
+				sequence: sequence.to_owned(),
+				mark: mark.to_owned(),
+				old: old_mark,
+				new: new_mark,
+			});
+		}
+	}
+
+	diff_markers(&sequence, (old, old.markers()), (new, new.markers()), None, changes);
+	diff_cuts(old_file, old, new_file, new, changes);
+}
+
+/// Markers are matched by their GUID, markers without one by their start.
+fn same_marker(old: &Marker, new: &Marker) -> bool {
+	if old.id.is_empty() || new.id.is_empty() {
+		old.id == new.id && close(old.start, new.start)
+	} else {
+		old.id == new.id
+	}
+}
+
+/// Markers of the part of the source a cut plays back, the ones shown on the timeline.
+fn visible_markers(cut: &Cut) -> Vec<Marker> {
+	let (from, to) = cut.source_range();
+	cut.markers()
+		.iter()
+		.filter(|marker| marker.start > from.min(to) - EPSILON && marker.start < from.max(to) + EPSILON)
+		.cloned()
+		.collect()
+}
+
+/// Where a marker lies on the timeline, clip markers at the clip's base speed.
+fn marker_time(marker: &Marker, cut: Option<&Cut>) -> f64 {
+	match cut {
+		Some(cut) => cut.start + (marker.start - cut.in_point) / cut.speed().rate,
+		None => marker.start
+	}
+}
+
+/// Compares the markers of a sequence or, with `cuts` given, the
+/// markers of a cut with the ones of the cut it was matched with.
+fn diff_markers(
+	sequence: &str,
+	(old_seq, old): (&PremiereSequence, &[Marker]),
+	(new_seq, new): (&PremiereSequence, &[Marker]),
+	cuts: Option<(&Cut, &Cut)>,
+	changes: &mut Vec<Change>,
+) {
+	let old_cut = cuts.map(|(old_cut, _)| old_cut);
+	let new_cut = cuts.map(|(_, new_cut)| new_cut);
+	let old_at = |marker: &Marker| old_seq.timecode_at(marker_time(marker, old_cut));
+	let new_at = |marker: &Marker| new_seq.timecode_at(marker_time(marker, new_cut));
+	let position = |cut: Option<&Cut>, seq: &PremiereSequence| cut.map(|cut| CutPosition::new(cut, seq));
+	for old_marker in old {
+		match new.iter().find(|new_marker| same_marker(old_marker, new_marker)) {
+			Some(new_marker) if new_marker != old_marker => changes.push(Change::MarkerChanged {
+				sequence: sequence.to_owned(),
+				cut: position(new_cut, new_seq),
+				at: new_at(new_marker),
+				old: old_marker.clone(),
+				new: new_marker.clone(),
+			}),
+			Some(_) => {}
+			None => changes.push(Change::MarkerRemoved {
+				sequence: sequence.to_owned(),
+				cut: position(old_cut, old_seq),
+				at: old_at(old_marker),
+				marker: old_marker.clone(),
+			}),
+		}
+	}
+	for new_marker in new {
+		if !old.iter().any(|old_marker| same_marker(old_marker, new_marker)) {
+			changes.push(Change::MarkerAdded {
+				sequence: sequence.to_owned(),
+				cut: position(new_cut, new_seq),
+				at: new_at(new_marker),
+				marker: new_marker.clone(),
+			});
+		}
+	}
+}
+
+fn close(a: f64, b: f64) -> bool {
+	(a - b).abs() < EPSILON
+}
+
+/// What a cut plays back: the `ObjectUID` of its medium or nested sequence.
+fn source_key<'a>(cut: &'a Cut, file: &'a PremiereFile) -> &'a str {
+	match cut.source() {
+		CutSource::Medium(id) => file.medium(*id).map_or("", medium_key),
+		CutSource::Sequence(id) => file.sequences
+			.iter()
+			.find(|seq| seq.id == *id)
+			.map_or("", |seq| seq.uid.as_str()),
+	}
+}
+
+fn source_name(cut: &Cut, file: &PremiereFile) -> String {
+	match cut.source() {
+		CutSource::Medium(id) => file.medium(*id).map_or_else(String::new, |medium| medium.file_name().to_owned()),
+		CutSource::Sequence(id) => file.sequences
+			.iter()
+			.find(|seq| seq.id == *id)
+			.map_or_else(|| format!("sequence {}", id), |seq| seq.name.to_owned()),
+	}
+}
+
+fn diff_cuts(
+	old_file: &PremiereFile,
+	old: &PremiereSequence,
+	new_file: &PremiereFile,
+	new: &PremiereSequence,
+	changes: &mut Vec<Change>,
+) {
+	let old_cuts: Vec<&Cut> = old.cuts().iter().collect();
+	let new_cuts: Vec<&Cut> = new.cuts().iter().collect();
+	let same_source = |old_cut: &Cut, new_cut: &Cut| old_cut.kind == new_cut.kind
+		&& source_key(old_cut, old_file) == source_key(new_cut, new_file);
+	let unchanged = |old_cut: &Cut, new_cut: &Cut| old_cut.track == new_cut.track
+		&& close(old_cut.start, new_cut.start)
+		&& close(old_cut.end, new_cut.end)
+		&& close(old_cut.in_point, new_cut.in_point)
+		&& close(old_cut.out_point, new_cut.out_point);
+	let overlap = |old_cut: &Cut, new_cut: &Cut|
+		old_cut.out_point.min(new_cut.out_point) - old_cut.in_point.max(new_cut.in_point);
+
+	let mut matches: Vec<Option<usize>> = vec![None; old_cuts.len()];
+	let mut taken = vec![false; new_cuts.len()];
+	for (old_index, old_cut) in old_cuts.iter().enumerate() {
+		let found = (0..new_cuts.len()).find(|&new_index| !taken[new_index]
+			&& same_source(old_cut, new_cuts[new_index])
+			&& unchanged(old_cut, new_cuts[new_index]));
+		if let Some(new_index) = found {
+			taken[new_index] = true;
+			matches[old_index] = Some(new_index);
+		}
+	}
+	for (old_index, old_cut) in old_cuts.iter().enumerate() {
+		if matches[old_index].is_some() {
+			continue;
+		}
+		let found = (0..new_cuts.len())
+			.filter(|&new_index| !taken[new_index]
+				&& same_source(old_cut, new_cuts[new_index])
+				&& overlap(old_cut, new_cuts[new_index]) > -EPSILON)
+			.max_by(|&a, &b| {
+				let score = |index: usize| (
+					overlap(old_cut, new_cuts[index]),
+					-(new_cuts[index].start - old_cut.start).abs(),
+				);
+				score(a).partial_cmp(&score(b)).unwrap_or(std::cmp::Ordering::Equal)
+			});
+		if let Some(new_index) = found {
+			taken[new_index] = true;
+			matches[old_index] = Some(new_index);
+		}
+	}
+
+	let mut cut_changes: Vec<(f64, Change)> = Vec::new();
+	for (old_cut, found) in old_cuts.iter().zip(matches) {
+		let source = source_name(old_cut, old_file);
+		let old_position = CutPosition::new(old_cut, old);
+		let new_cut = match found {
+			Some(new_index) => new_cuts[new_index],
+			None => {
+				cut_changes.push((old_cut.start, Change::CutRemoved {
+					sequence: new.name.to_owned(),
+					source,
+					cut: old_position,
+				}));
+				continue;
+			}
+		};
+		let mut marker_changes = Vec::new();
+		diff_markers(
+			&new.name,
+			(old, &visible_markers(old_cut)),
+			(new, &visible_markers(new_cut)),
+			Some((old_cut, new_cut)),
+			&mut marker_changes
+		);
+		cut_changes.extend(marker_changes.into_iter().map(|change| (new_cut.start, change)));
+		if unchanged(old_cut, new_cut) {
+			continue;
+		}
+		let new_position = CutPosition::new(new_cut, new);
+		let same_range = close(old_cut.in_point, new_cut.in_point)
+			&& close(old_cut.out_point, new_cut.out_point)
+			&& close(old_cut.end - old_cut.start, new_cut.end - new_cut.start);
+		let change = if same_range {
+			Change::CutMoved { sequence: new.name.to_owned(), source, old: old_position, new: new_position }
+		} else {
+			Change::CutTrimmed { sequence: new.name.to_owned(), source, old: old_position, new: new_position }
+		};
+		cut_changes.push((new_cut.start, change));
+	}
+	for (new_cut, _) in new_cuts.iter().zip(taken).filter(|(_, taken)| !taken) {
+		cut_changes.push((new_cut.start, Change::CutAdded {
+			sequence: new.name.to_owned(),
+			source: source_name(new_cut, new_file),
+			cut: CutPosition::new(new_cut, new),
+		}));
+	}
+	cut_changes.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+	changes.extend(cut_changes.into_iter().map(|(_, change)| change));
+}
@@ -5,6 +5,8 @@ use crate::errors::{NotFoundError, NotFoundErrorData, Error};
 pub trait ElementGetExt {
 	fn get(&self, name: &str) -> Result<&Element, Error>;
 	fn get_attr(&self, name: &str) -> Result<&str, Error>;
+	/// Depth-first search for the first descendant with the given name.
+	fn find(&self, name: &str) -> Option<&Element>;
 }
 
 impl ElementGetExt for Element {
@@ -43,4 +45,16 @@ impl ElementGetExt for Element {
 			)
 		}
 	}
+
+	fn find(&self, name: &str) -> Option<&Element> {
+		for child in self.children() {
+			if child.name() == name {
+				return Some(child);
+			}
+			if let Some(found) = child.find(name) {
+				return Some(found);
+			}
+		}
+		None
+	}
 }
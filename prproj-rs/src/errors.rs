@@ -1,9 +1,19 @@
 use std::fmt;
+use std::io;
 use crate::element::Element;
 
 #[derive(Debug)]
 pub enum Error {
-	NotFound(NotFoundError)
+	NotFound(NotFoundError),
+	/// An element holds text which isn't a valid value, e.g. a non-numeric ID.
+	Invalid(InvalidValueData),
+	/// Reading a project file failed.
+	Io(io::Error),
+	/// The project isn't well-formed XML.
+	Xml(minidom::Error),
+	/// The project isn't well-formed XML, found while reading it asynchronously.
+	#[cfg(feature = "async")]
+	AsyncXml(quick_xml_async::Error),
 }
 
 #[derive(Debug)]
@@ -26,17 +36,33 @@ impl MultipleNotFoundErrorData {
 	}
 }
 
+#[derive(Debug)]
+pub struct InvalidValueData {
+	name: String,
+	value: String,
+}
+
+impl InvalidValueData {
+	pub fn new(name: String, value: String) -> Self {
+		Self {
+			name,
+			value
+		}
+	}
+}
+
 #[derive(Debug)]
 pub struct NotFoundErrorData {
 	name_of_not_found: String,
-	in_elem: Element,
+	// boxed to keep `Error` small
+	in_elem: Box<Element>,
 }
 
 impl NotFoundErrorData {
 	pub fn new(name: String, in_elem: Element) -> Self {
 		Self {
 			name_of_not_found: name,
-			in_elem
+			in_elem: Box::new(in_elem)
 		}
 	}
 }
@@ -47,6 +73,49 @@ impl fmt::Display for Error {
 			Error::NotFound(not_found_error) => {
 				not_found_error.fmt(f)
 			}
+			Error::Invalid(data) => {
+				write!(f, "Invalid value \"{}\" in \"{}\"!", data.value, data.name)
+			}
+			Error::Io(io_error) => {
+				io_error.fmt(f)
+			}
+			Error::Xml(xml_error) => {
+				xml_error.fmt(f)
+			}
+			#[cfg(feature = "async")]
+			Error::AsyncXml(xml_error) => {
+				xml_error.fmt(f)
+			}
+		}
+	}
+}
+
+impl From<io::Error> for Error {
+	fn from(error: io::Error) -> Self {
+		Error::Io(error)
+	}
+}
+
+impl From<minidom::Error> for Error {
+	fn from(error: minidom::Error) -> Self {
+		match error {
+			// e.g. a truncated gzip stream
+			minidom::Error::IoError(io_error)
+			| minidom::Error::XmlError(quick_xml::Error::Io(io_error)) => Error::Io(io_error),
+			xml_error => Error::Xml(xml_error),
+		}
+	}
+}
+
+#[cfg(feature = "async")]
+impl From<quick_xml_async::Error> for Error {
+	fn from(error: quick_xml_async::Error) -> Self {
+		match error {
+			quick_xml_async::Error::Io(io_error) => Error::Io(
+				std::sync::Arc::try_unwrap(io_error)
+					.unwrap_or_else(|shared| io::Error::new(shared.kind(), shared.to_string()))
+			),
+			xml_error => Error::AsyncXml(xml_error),
 		}
 	}
 }
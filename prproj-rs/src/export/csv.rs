@@ -0,0 +1,49 @@
+//! Comma separated list of the clips of a sequence, one row per clip.
+
+use crate::premiere::{PremiereSequence, TrackKind};
+use crate::PremiereFile;
+use super::{clip_name, clip_path, format_seconds};
+
+const HEADER: &str = "id,track,start,end,start_timecode,end_timecode,in_point,out_point,speed,reverse,name,path";
+
+/// Writes the clips of all tracks, video tracks first, ordered by their start.
+pub fn write(project: &PremiereFile, seq: &PremiereSequence) -> String {
+	let mut cuts: Vec<_> = seq.cuts().iter().collect();
+	cuts.sort_by(|a, b| {
+		(a.kind == TrackKind::Audio, a.track)
+			.cmp(&(b.kind == TrackKind::Audio, b.track))
+			.then(a.start.partial_cmp(&b.start).unwrap())
+	});
+
+	let mut csv = String::from(HEADER);
+	csv.push('\n');
+	for cut in cuts {
+		let fields = [
+			cut.id.to_string(),
+			cut.kind.track_name(cut.track),
+			format_seconds(cut.start),
+			format_seconds(cut.end),
+			seq.timecode_at(cut.start).to_string(),
+			seq.timecode_at(cut.end).to_string(),
+			format_seconds(cut.in_point),
+			format_seconds(cut.out_point),
+			format_seconds(cut.speed().rate),
+			cut.speed().reverse.to_string(),
+			clip_name(project, cut),
+			clip_path(project, cut).unwrap_or_default().to_owned(),
+		];
+		let escaped: Vec<String> = fields.iter().map(|field| escape(field)).collect();
+		csv.push_str(&escaped.join(","));
+		csv.push('\n');
+	}
+	csv
+}
+
+/// Quotes fields with commas, quotes or line breaks.
+fn escape(field: &str) -> String {
+	if field.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		field.to_owned()
+	}
+}
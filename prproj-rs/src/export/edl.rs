@@ -0,0 +1,156 @@
+//! CMX 3600 edit decision lists.
+//!
+//! Video events are the visible video segments, so clips on higher tracks
+//! replace the ones below. Audio events are written per track as channels
+//! `A`, `A2`, `A3`... Transitions joining two events become dissolves.
+//! Every event uses the reel `AX` followed by a `* FROM CLIP NAME:` comment.
+
+use crate::premiere::{Cut, PremiereSequence, TrackKind, Transition};
+use crate::timecode::Timecode;
+use crate::PremiereFile;
+use super::clip_name;
+
+const REEL: &str = "AX";
+/// Times closer than this are considered the same edit point.
+const EPSILON: f64 = 1e-6;
+
+/// A part of a cut played back from `start` to `end` on the timeline,
+/// with the index of the cut in the sequence.
+struct Segment<'a> {
+	index: usize,
+	cut: &'a Cut,
+	start: f64,
+	end: f64,
+}
+
+/// Writes the EDL of a sequence.
+pub fn write(project: &PremiereFile, seq: &PremiereSequence) -> String {
+	let mut edl = format!(
+		"TITLE: {}\nFCM: {}\n",
+		seq.name,
+		if seq.settings().drop_frame { "DROP FRAME" } else { "NON-DROP FRAME" }
+	);
+	let mut event = 0;
+
+	let video: Vec<Segment> = seq.timeline()
+		.items()
+		.iter()
+		.filter(|item| item.end > item.start)
+		.filter_map(|item| seq.cuts().get(item.cut).map(|cut| Segment {
+			index: item.cut,
+			cut,
+			start: item.start,
+			end: item.end,
+		}))
+		.collect();
+	let video_transitions: Vec<&Transition> = seq.transitions()
+		.iter()
+		.filter(|transition| transition.kind == TrackKind::Video)
+		.collect();
+	write_events(project, seq, &video, &video_transitions, "V", &mut event, &mut edl);
+
+	let audio_tracks = seq.cuts()
+		.iter()
+		.filter(|cut| cut.kind == TrackKind::Audio)
+		.map(|cut| cut.track + 1)
+		.max()
+		.unwrap_or(0);
+	for track in 0..audio_tracks {
+		let mut audio: Vec<Segment> = seq
+			.cuts_on(TrackKind::Audio, track)
+			.into_iter()
+			.map(|(index, cut)| Segment { index, cut, start: cut.start, end: cut.end })
+			.collect();
+		audio.sort_by(|a, b| a.start.partial_cmp(&b.start).unwrap());
+		let transitions: Vec<&Transition> = seq.transitions_on(TrackKind::Audio, track).collect();
+		let channel = if track == 0 { String::from("A") } else { format!("A{}", track + 1) };
+		write_events(project, seq, &audio, &transitions, &channel, &mut event, &mut edl);
+	}
+	edl
+}
+
+/// The transition joining two segments which are next to each other.
+fn joining<'a>(transitions: &[&'a Transition], from: &Segment, to: &Segment) -> Option<&'a Transition> {
+	if (from.end - to.start).abs() > EPSILON {
+		return None;
+	}
+	transitions
+		.iter()
+		.find(|transition| transition.from_cut == Some(from.index) && transition.to_cut == Some(to.index))
+		.copied()
+}
+
+fn write_events(
+	project: &PremiereFile,
+	seq: &PremiereSequence,
+	segments: &[Segment],
+	transitions: &[&Transition],
+	channel: &str,
+	event: &mut usize,
+	edl: &mut String,
+) {
+	let fps = seq.settings().fps();
+	let drop_frame = seq.settings().drop_frame;
+	let source_timecode = |seconds: f64| Timecode::from_seconds(seconds, fps, drop_frame);
+	let line = |event: usize, edit: &str, source: (f64, f64), record: (f64, f64)| format!(
+		"{:03}  {:<8} {:<5} {:<8} {} {} {} {}\n",
+		event,
+		REEL,
+		channel,
+		edit,
+		source_timecode(source.0),
+		source_timecode(source.1),
+		seq.timecode_at(record.0),
+		seq.timecode_at(record.1)
+	);
+
+	for (i, segment) in segments.iter().enumerate() {
+		let into = if i > 0 { joining(transitions, &segments[i - 1], segment) } else { None };
+		let out_of = segments.get(i + 1).and_then(|next| joining(transitions, segment, next));
+		// The outgoing clip ends where the dissolve starts
+		let end = out_of.map_or(segment.end, |transition| transition.start.max(segment.start));
+		let start = into.map_or(segment.start, |transition| transition.start);
+		let source = source_span(segment.cut, start, end);
+
+		*event += 1;
+		edl.push('\n');
+		match into {
+			Some(transition) => {
+				let previous = segments[i - 1].cut;
+				let held = previous.source_time_at(transition.start);
+				let frames = crate::timecode::to_frames(transition.duration(), fps);
+				edl.push_str(&line(*event, "C", (held, held), (start, start)));
+				edl.push_str(&line(*event, &format!("D    {:03}", frames), source, (start, end)));
+				edl.push_str(&format!("* FROM CLIP NAME: {}\n", clip_name(project, previous)));
+				edl.push_str(&format!("* TO CLIP NAME: {}\n", clip_name(project, segment.cut)));
+			}
+			None => {
+				edl.push_str(&line(*event, "C", source, (start, end)));
+				edl.push_str(&format!("* FROM CLIP NAME: {}\n", clip_name(project, segment.cut)));
+			}
+		}
+		let speed = segment.cut.speed();
+		if speed.reverse || (speed.rate - 1.).abs() > f64::EPSILON {
+			let frames_per_second = speed.rate * fps * if speed.reverse { -1. } else { 1. };
+			let entry = segment.cut.source_time_at(start);
+			edl.push_str(&format!(
+				"M2   {:<8} {:>6.1}    {}\n",
+				REEL,
+				frames_per_second,
+				source_timecode(entry)
+			));
+		}
+	}
+}
+
+/// Source in and out of a part of a cut. With speed changes the source
+/// duration equals the record duration and an `M2` line gives the speed.
+fn source_span(cut: &Cut, start: f64, end: f64) -> (f64, f64) {
+	let speed = cut.speed();
+	if speed.reverse || (speed.rate - 1.).abs() > f64::EPSILON {
+		let entry = cut.source_time_at(start);
+		(entry, entry + (end - start))
+	} else {
+		(cut.source_time_at(start), cut.source_time_at(end))
+	}
+}
@@ -0,0 +1,212 @@
+//! Final Cut Pro 7 XML (`xmeml` version 4), which most editors import.
+
+use std::fmt::Write;
+
+use crate::premiere::{Cut, CutSource, PremiereSequence, TrackKind, Transition, TransitionAlignment};
+use crate::timecode::to_frames;
+use crate::PremiereFile;
+use super::{clip_name, escape_xml, file_url, tracks};
+
+/// Transitions FCP 7 knows by `(match name, effect ID, category)`,
+/// other video transitions become a Cross Dissolve and audio ones a Cross Fade.
+const TRANSITION_EFFECTS: [(&str, &str, &str); 3] = [
+	("AE.ADBE Cross Dissolve New", "Cross Dissolve", "Dissolve"),
+	("AE.ADBE Additive Dissolve", "Additive Dissolve", "Dissolve"),
+	("AE.ADBE Dip To Black", "Dip to Color Dissolve", "Dissolve"),
+];
+
+/// Writes a sequence with its video and audio tracks as `xmeml`.
+pub fn write(project: &PremiereFile, seq: &PremiereSequence) -> String {
+	let mut writer = Writer {
+		project,
+		seq,
+		fps: seq.settings().fps(),
+		out: String::new(),
+		files: Vec::new(),
+	};
+	writer.sequence();
+	writer.out
+}
+
+struct Writer<'a> {
+	project: &'a PremiereFile,
+	seq: &'a PremiereSequence,
+	fps: f64,
+	out: String,
+	/// Paths of the files already written, `file-{index + 1}` is their ID.
+	files: Vec<String>,
+}
+
+impl<'a> Writer<'a> {
+	fn line(&mut self, depth: usize, text: &str) {
+		writeln!(self.out, "{}{}", "\t".repeat(depth), text).unwrap();
+	}
+
+	fn frames(&self, seconds: f64) -> i64 {
+		to_frames(seconds, self.fps)
+	}
+
+	fn rate(&mut self, depth: usize) {
+		let timebase = self.fps.round() as i64;
+		let ntsc = (self.fps - timebase as f64).abs() > 1e-3;
+		self.line(depth, "<rate>");
+		self.line(depth + 1, &format!("<timebase>{}</timebase>", timebase));
+		self.line(depth + 1, &format!("<ntsc>{}</ntsc>", if ntsc { "TRUE" } else { "FALSE" }));
+		self.line(depth, "</rate>");
+	}
+
+	fn sequence(&mut self) {
+		let seq = self.seq;
+		self.line(0, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+		self.line(0, "<!DOCTYPE xmeml>");
+		self.line(0, "<xmeml version=\"4\">");
+		self.line(1, &format!("<sequence id=\"sequence-{}\">", seq.id));
+		self.line(2, &format!("<name>{}</name>", escape_xml(&seq.name)));
+		self.line(2, &format!("<duration>{}</duration>", self.frames(seq.end())));
+		self.rate(2);
+		self.line(2, "<timecode>");
+		self.rate(3);
+		self.line(3, &format!("<string>{}</string>", seq.start_timecode()));
+		self.line(3, &format!("<frame>{}</frame>", self.frames(seq.start_time())));
+		self.line(3, &format!("<displayformat>{}</displayformat>", if seq.settings().drop_frame { "DF" } else { "NDF" }));
+		self.line(2, "</timecode>");
+		self.line(2, "<media>");
+
+		self.line(3, "<video>");
+		self.line(4, "<format>");
+		self.line(5, "<samplecharacteristics>");
+		self.rate(6);
+		self.line(6, &format!("<width>{}</width>", seq.size.width));
+		self.line(6, &format!("<height>{}</height>", seq.size.height));
+		self.line(5, "</samplecharacteristics>");
+		self.line(4, "</format>");
+		for (index, cuts) in tracks(seq, TrackKind::Video).into_iter().enumerate() {
+			self.track(TrackKind::Video, index, &cuts);
+		}
+		self.line(3, "</video>");
+
+		self.line(3, "<audio>");
+		for (index, cuts) in tracks(seq, TrackKind::Audio).into_iter().enumerate() {
+			self.track(TrackKind::Audio, index, &cuts);
+		}
+		self.line(3, "</audio>");
+
+		self.line(2, "</media>");
+		self.line(1, "</sequence>");
+		self.line(0, "</xmeml>");
+	}
+
+	fn track(&mut self, kind: TrackKind, index: usize, cuts: &[&Cut]) {
+		self.line(4, "<track>");
+		// Clips and transitions in the order of their start
+		let mut items: Vec<(f64, Option<&Cut>, Option<usize>)> = cuts
+			.iter()
+			.map(|cut| (cut.start, Some(*cut), None))
+			.collect();
+		for (transition_index, transition) in self.seq.transitions().iter().enumerate() {
+			if transition.kind == kind && transition.track == index {
+				items.push((transition.start, None, Some(transition_index)));
+			}
+		}
+		items.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap().then(a.1.is_some().cmp(&b.1.is_some())));
+		for item in items {
+			match item {
+				(_, Some(cut), _) => self.clip_item(cut),
+				(_, _, Some(transition)) => self.transition_item(transition),
+				_ => ()
+			}
+		}
+		self.line(4, "</track>");
+	}
+
+	fn clip_item(&mut self, cut: &Cut) {
+		let media_type = match cut.kind {
+			TrackKind::Video => "video",
+			TrackKind::Audio => "audio",
+		};
+		let (source_in, source_out) = cut.source_range();
+		self.line(5, &format!("<clipitem id=\"clipitem-{}\">", cut.id));
+		self.line(6, &format!("<name>{}</name>", escape_xml(&clip_name(self.project, cut))));
+		self.line(6, &format!("<enabled>{}</enabled>", if cut.is_disabled() { "FALSE" } else { "TRUE" }));
+		self.rate(6);
+		self.line(6, &format!("<start>{}</start>", self.frames(cut.start)));
+		self.line(6, &format!("<end>{}</end>", self.frames(cut.end)));
+		self.line(6, &format!("<in>{}</in>", self.frames(source_in)));
+		self.line(6, &format!("<out>{}</out>", self.frames(source_out)));
+
+		let medium = match cut.source() {
+			CutSource::Medium(id) => self.project.medium(*id),
+			CutSource::Sequence(_) => None
+		};
+		if let Some(medium) = medium {
+			let path = medium.file_path().to_owned();
+			match self.files.iter().position(|file| *file == path) {
+				Some(file) => self.line(6, &format!("<file id=\"file-{}\"/>", file + 1)),
+				None => {
+					self.files.push(path.to_owned());
+					self.line(6, &format!("<file id=\"file-{}\">", self.files.len()));
+					self.line(7, &format!("<name>{}</name>", escape_xml(medium.file_name())));
+					self.line(7, &format!("<pathurl>{}</pathurl>", escape_xml(&file_url(&path))));
+					self.rate(7);
+					self.line(6, "</file>");
+				}
+			}
+		}
+
+		// Links list the clip itself as well as its partners
+		if cut.is_linked() {
+			let mut ids: Vec<u32> = vec![cut.id];
+			ids.extend(cut.linked());
+			ids.sort_unstable();
+			for id in ids {
+				let linked_type = self.seq.cuts()
+					.iter()
+					.find(|other| other.id == id)
+					.map_or(media_type, |other| if other.kind == TrackKind::Video { "video" } else { "audio" });
+				self.line(6, "<link>");
+				self.line(7, &format!("<linkclipref>clipitem-{}</linkclipref>", id));
+				self.line(7, &format!("<mediatype>{}</mediatype>", linked_type));
+				self.line(6, "</link>");
+			}
+		}
+		self.line(5, "</clipitem>");
+	}
+
+	fn transition_item(&mut self, index: usize) {
+		let transition = &self.seq.transitions()[index];
+		let alignment = match (transition.alignment, transition.from_cut, transition.to_cut) {
+			(_, None, Some(_)) => "start-black",
+			(_, Some(_), None) => "end-black",
+			(TransitionAlignment::Start, _, _) => "start",
+			(TransitionAlignment::Center, _, _) => "center",
+			(TransitionAlignment::End, _, _) => "end",
+		};
+		let media_type = if transition.kind == TrackKind::Video { "video" } else { "audio" };
+		let (name, category) = transition_effect(transition);
+		let (start, end) = (self.frames(transition.start), self.frames(transition.end));
+		self.line(5, "<transitionitem>");
+		self.rate(6);
+		self.line(6, &format!("<start>{}</start>", start));
+		self.line(6, &format!("<end>{}</end>", end));
+		self.line(6, &format!("<alignment>{}</alignment>", alignment));
+		self.line(6, "<effect>");
+		self.line(7, &format!("<name>{}</name>", escape_xml(&transition.display_name)));
+		self.line(7, &format!("<effectid>{}</effectid>", name));
+		self.line(7, &format!("<effectcategory>{}</effectcategory>", category));
+		self.line(7, "<effecttype>transition</effecttype>");
+		self.line(7, &format!("<mediatype>{}</mediatype>", media_type));
+		self.line(6, "</effect>");
+		self.line(5, "</transitionitem>");
+	}
+}
+
+/// FCP 7's effect ID and category of a Premiere transition.
+fn transition_effect(transition: &Transition) -> (&'static str, &'static str) {
+	if transition.kind == TrackKind::Audio {
+		return ("Cross Fade (+3dB)", "Audio Transitions");
+	}
+	TRANSITION_EFFECTS
+		.iter()
+		.find(|(match_name, _, _)| *match_name == transition.match_name)
+		.map_or(("Cross Dissolve", "Dissolve"), |(_, name, category)| (name, category))
+}
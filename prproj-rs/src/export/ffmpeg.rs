@@ -0,0 +1,347 @@
+//! Cut lists to render sequences with FFmpeg.
+//!
+//! Only cuts of media are rendered. Nested sequences show up as gaps unless
+//! the reader flattens them, see `PremiereReader::set_flatten_nested`.
+//! Time remapping is approximated with the clip's base speed.
+//! Disabled clips and muted tracks or ones with their output turned off
+//! are left out, as Premiere doesn't play them.
+
+use std::error;
+use std::fmt;
+
+use crate::premiere::{ChannelType, Cut, CutSource, PremiereSequence, TrackKind};
+use crate::timeline::Timeline;
+use crate::{PremiereFile, TICKS_PER_SECOND};
+use super::{clip_path, format_seconds};
+
+/// A visible cut the concat demuxer can't play, as it doesn't play at 100%
+/// or is reversed. `FilterGraph` renders it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpeedChange {
+	pub cut: u32,
+	/// Timeline position of the cut in seconds.
+	pub start: f64,
+	pub rate: f64,
+	pub reverse: bool,
+}
+
+impl fmt::Display for SpeedChange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"clip {} at {}s plays at {}%{}, which a concat list can't render",
+			self.cut,
+			format_seconds(self.start),
+			format_seconds(self.rate * 100.),
+			if self.reverse { " in reverse" } else { "" }
+		)
+	}
+}
+
+impl error::Error for SpeedChange {}
+
+/// Writes an FFmpeg concat demuxer file (`ffconcat`) listing the visible video
+/// segments of a sequence with their `inpoint` and `outpoint`.
+///
+/// The concat demuxer can't render gaps nor speed changes, gaps are left
+/// out and a sequence with a visible speed change or reversed clip fails
+/// with `SpeedChange`, use a `FilterGraph` for those.
+/// Render it with `ffmpeg -f concat -safe 0 -i list.ffconcat out.mp4`.
+pub fn concat_list(project: &PremiereFile, seq: &PremiereSequence) -> Result<String, SpeedChange> {
+	let mut list = String::from("ffconcat version 1.0\n");
+	for (cut, start, end) in visible_segments(seq) {
+		let path = match clip_path(project, cut) {
+			Some(path) => path,
+			None => continue
+		};
+		let speed = cut.speed();
+		if (speed.rate - 1.).abs() > f64::EPSILON || speed.reverse || !speed.remap.is_empty() {
+			return Err(SpeedChange {
+				cut: cut.id,
+				start,
+				rate: speed.rate,
+				reverse: speed.reverse,
+			});
+		}
+		let (from, to) = source_span(cut, start, end);
+		list.push_str(&format!("file '{}'\n", escape_path(path)));
+		list.push_str(&format!("inpoint {}\n", format_seconds(from)));
+		list.push_str(&format!("outpoint {}\n", format_seconds(to)));
+	}
+	Ok(list)
+}
+
+/// Input files and `-filter_complex` graph rendering a whole sequence:
+/// every clip is trimmed, gaps become black or silence and the video
+/// is scaled and padded to the sequence's `Size`.
+/// The graph's outputs are labeled `[v]` and `[a]`.
+#[derive(Clone, Debug, Default)]
+pub struct FilterGraph {
+	/// Paths of the input files, the index is the number of the input.
+	pub inputs: Vec<String>,
+	pub filter: String,
+	pub has_video: bool,
+	pub has_audio: bool,
+}
+
+impl FilterGraph {
+	pub fn new(project: &PremiereFile, seq: &PremiereSequence) -> Self {
+		let mut graph = FilterGraph::default();
+		let mut filters: Vec<String> = Vec::new();
+		let end = seq.end();
+
+		let video = graph.video_chains(project, seq, end, &mut filters);
+		if !video.is_empty() {
+			filters.push(format!("{}concat=n={}:v=1:a=0[v]", video.concat(), video.len()));
+			graph.has_video = true;
+		}
+
+		let audio_tracks = seq
+			.cuts()
+			.iter()
+			.filter(|cut| cut.kind == TrackKind::Audio)
+			.map(|cut| cut.track)
+			.max()
+			.map_or(0, |last| last + 1);
+		let mut mixed: Vec<String> = Vec::new();
+		for track in 0..audio_tracks {
+			let segments = graph.audio_chains(project, seq, track, end, &mut filters);
+			if segments.is_empty() {
+				continue;
+			}
+			let label = format!("[a{}]", track);
+			filters.push(format!("{}concat=n={}:v=0:a=1{}", segments.concat(), segments.len(), label));
+			mixed.push(label);
+		}
+		match mixed.len() {
+			0 => (),
+			1 => filters.push(format!("{}anull[a]", mixed[0])),
+			len => filters.push(format!(
+				"{}amix=inputs={}:duration=longest:normalize=0[a]",
+				mixed.concat(), len
+			))
+		}
+		graph.has_audio = !mixed.is_empty();
+
+		graph.filter = filters.join(";\n");
+		graph
+	}
+
+	/// Arguments for `ffmpeg` rendering the graph to `output`.
+	pub fn args(&self, output: &str) -> Vec<String> {
+		let mut args: Vec<String> = Vec::new();
+		for input in self.inputs.iter() {
+			args.push(String::from("-i"));
+			args.push(input.to_owned());
+		}
+		args.push(String::from("-filter_complex"));
+		args.push(self.filter.to_owned());
+		if self.has_video {
+			args.extend(vec![String::from("-map"), String::from("[v]")]);
+		}
+		if self.has_audio {
+			args.extend(vec![String::from("-map"), String::from("[a]")]);
+		}
+		args.push(output.to_owned());
+		args
+	}
+
+	fn input(&mut self, path: &str) -> usize {
+		match self.inputs.iter().position(|input| input == path) {
+			Some(index) => index,
+			None => {
+				self.inputs.push(path.to_owned());
+				self.inputs.len() - 1
+			}
+		}
+	}
+
+	/// Pushes a chain for every visible video segment and gap,
+	/// returns the labels of their outputs in order.
+	fn video_chains(
+		&mut self,
+		project: &PremiereFile,
+		seq: &PremiereSequence,
+		end: f64,
+		filters: &mut Vec<String>
+	) -> Vec<String> {
+		let size = seq.settings().frame_size;
+		let (width, height) = if size.width > 0 { (size.width, size.height) } else { (seq.size.width, seq.size.height) };
+		let rate = frame_rate(seq.settings().frame_rate);
+		let black = |duration: f64| format!(
+			"color=c=black:s={}x{}:r={}:d={}",
+			width, height, rate, format_seconds(duration)
+		);
+
+		let mut labels: Vec<String> = Vec::new();
+		let mut time = 0.;
+		for (cut, start, segment_end) in visible_segments(seq) {
+			let path = match clip_path(project, cut) {
+				Some(path) => path,
+				None => continue
+			};
+			if start > time {
+				labels.push(format!("[v{}]", labels.len()));
+				filters.push(format!("{}{}", black(start - time), labels.last().unwrap()));
+			}
+			let input = self.input(path);
+			let (from, to) = source_span(cut, start, segment_end);
+			let mut chain = format!(
+				"[{}:v]trim=start={}:end={},setpts=PTS-STARTPTS",
+				input, format_seconds(from), format_seconds(to)
+			);
+			if cut.speed().reverse {
+				chain.push_str(",reverse");
+			}
+			if (cut.speed().rate - 1.).abs() > f64::EPSILON {
+				chain.push_str(&format!(",setpts=PTS/{}", cut.speed().rate));
+			}
+			chain.push_str(&format!(
+				",scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2,setsar=1,fps={r}",
+				w = width, h = height, r = rate
+			));
+			labels.push(format!("[v{}]", labels.len()));
+			filters.push(format!("{}{}", chain, labels.last().unwrap()));
+			time = segment_end;
+		}
+		if !labels.is_empty() && end > time {
+			labels.push(format!("[v{}]", labels.len()));
+			filters.push(format!("{}{}", black(end - time), labels.last().unwrap()));
+		}
+		labels
+	}
+
+	/// Like `video_chains` for the cuts of an audio track, gaps are silent.
+	fn audio_chains(
+		&mut self,
+		project: &PremiereFile,
+		seq: &PremiereSequence,
+		track: usize,
+		end: f64,
+		filters: &mut Vec<String>
+	) -> Vec<String> {
+		let settings = seq.settings();
+		let sample_rate = if settings.audio_sample_rate > 0 { settings.audio_sample_rate } else { 48000 };
+		let layout = match settings.audio_channels {
+			ChannelType::Mono => "mono",
+			ChannelType::Surround51 => "5.1",
+			_ => "stereo"
+		};
+		let silence = |duration: f64| format!(
+			"anullsrc=r={}:cl={},atrim=duration={}",
+			sample_rate, layout, format_seconds(duration)
+		);
+
+		let mut cuts: Vec<&Cut> = seq
+			.cuts()
+			.iter()
+			.filter(|cut| cut.kind == TrackKind::Audio && cut.track == track)
+			.filter(|cut| matches!(cut.source(), CutSource::Medium(_)) && plays(seq, cut))
+			.collect();
+		cuts.sort_by(|a, b| a.start.partial_cmp(&b.start).unwrap());
+
+		let mut labels: Vec<String> = Vec::new();
+		let mut time = 0.;
+		for cut in cuts {
+			let path = match clip_path(project, cut) {
+				Some(path) => path,
+				None => continue
+			};
+			let start = cut.start.max(time);
+			if start >= cut.end {
+				continue;
+			}
+			if start > time {
+				labels.push(format!("[a{}_{}]", track, labels.len()));
+				filters.push(format!("{}{}", silence(start - time), labels.last().unwrap()));
+			}
+			let input = self.input(path);
+			let (from, to) = source_span(cut, start, cut.end);
+			let mut chain = format!(
+				"[{}:a]atrim=start={}:end={},asetpts=PTS-STARTPTS",
+				input, format_seconds(from), format_seconds(to)
+			);
+			if cut.speed().reverse {
+				chain.push_str(",areverse");
+			}
+			if (cut.speed().rate - 1.).abs() > f64::EPSILON {
+				chain.push_str(&atempo(cut.speed().rate));
+			}
+			chain.push_str(&format!(",aresample={},aformat=channel_layouts={}", sample_rate, layout));
+			labels.push(format!("[a{}_{}]", track, labels.len()));
+			filters.push(format!("{}{}", chain, labels.last().unwrap()));
+			time = cut.end;
+		}
+		if !labels.is_empty() && end > time {
+			labels.push(format!("[a{}_{}]", track, labels.len()));
+			filters.push(format!("{}{}", silence(end - time), labels.last().unwrap()));
+		}
+		labels
+	}
+}
+
+/// Visible parts of the video cuts in order, with their start and end on the timeline.
+/// Unlike in the sequence's timeline, tracks with their output turned off don't hide the ones below.
+fn visible_segments(seq: &PremiereSequence) -> Vec<(&Cut, f64, f64)> {
+	let mut timeline = Timeline::default();
+	for (index, cut) in seq.cuts().iter().enumerate() {
+		if cut.kind == TrackKind::Video && plays(seq, cut) {
+			timeline.add(index, cut.start, cut.end);
+		}
+	}
+	timeline
+		.items()
+		.iter()
+		.filter(|item| item.end > item.start)
+		.filter_map(|item| seq.cuts().get(item.cut).map(|cut| (cut, item.start, item.end)))
+		.collect()
+}
+
+/// Whether Premiere plays the cut: it's enabled and its track isn't muted or turned off.
+fn plays(seq: &PremiereSequence, cut: &Cut) -> bool {
+	!cut.is_disabled() && !seq.is_track_muted(cut.kind, cut.track)
+}
+
+/// Part of the source of `cut` shown from `start` to `end` on the timeline.
+fn source_span(cut: &Cut, start: f64, end: f64) -> (f64, f64) {
+	let (a, b) = (cut.source_time_at(start), cut.source_time_at(end));
+	(a.min(b), a.max(b))
+}
+
+/// Frame rate given in ticks per frame as a fraction like `30000/1001`.
+fn frame_rate(ticks_per_frame: u64) -> String {
+	if ticks_per_frame == 0 {
+		return String::from("25");
+	}
+	let gcd = |mut a: u64, mut b: u64| {
+		while b != 0 {
+			let rest = a % b;
+			a = b;
+			b = rest;
+		}
+		a
+	};
+	let divisor = gcd(TICKS_PER_SECOND, ticks_per_frame);
+	let (numerator, denominator) = (TICKS_PER_SECOND / divisor, ticks_per_frame / divisor);
+	if denominator == 1 {
+		numerator.to_string()
+	} else {
+		format!("{}/{}", numerator, denominator)
+	}
+}
+
+/// `atempo` only takes factors from 0.5 to 100, so slower ones are chained.
+fn atempo(mut rate: f64) -> String {
+	let mut filters = String::new();
+	while rate < 0.5 {
+		filters.push_str(",atempo=0.5");
+		rate /= 0.5;
+	}
+	filters.push_str(&format!(",atempo={}", rate));
+	filters
+}
+
+/// Escapes single quotes of a path for the concat demuxer.
+fn escape_path(path: &str) -> String {
+	path.replace('\'', "'\\''")
+}
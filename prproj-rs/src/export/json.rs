@@ -0,0 +1,63 @@
+//! A sequence as JSON: its settings, clips and transitions.
+//!
+//! Unlike serializing the model, sources are resolved to names and paths
+//! and tracks are named like in Premiere, e.g. `"V1"`.
+
+use serde_json::{json, Value};
+
+use crate::premiere::{Cut, CutSource, PremiereSequence};
+use crate::PremiereFile;
+use super::clip_name;
+
+/// Writes a sequence as pretty-printed JSON, see `sequence`.
+pub fn write(project: &PremiereFile, seq: &PremiereSequence) -> String {
+	serde_json::to_string_pretty(&sequence(project, seq)).unwrap() + "\n"
+}
+
+/// Settings of a sequence with its `clips` and `transitions`.
+pub fn sequence(project: &PremiereFile, seq: &PremiereSequence) -> Value {
+	let settings = seq.settings();
+	json!({
+		"id": seq.id,
+		"name": seq.name(),
+		"width": seq.size.width,
+		"height": seq.size.height,
+		"fps": settings.fps(),
+		"drop_frame": settings.drop_frame,
+		"audio_sample_rate": settings.audio_sample_rate,
+		"start_timecode": seq.start_timecode().to_string(),
+		"duration": seq.end(),
+		"clips": seq.cuts().iter().map(|cut| self::cut(project, cut)).collect::<Vec<_>>(),
+		"transitions": seq.transitions()
+			.iter()
+			.map(|transition| json!({
+				"track": transition.kind.track_name(transition.track),
+				"start": transition.start,
+				"end": transition.end,
+				"name": transition.display_name,
+				"match_name": transition.match_name,
+			}))
+			.collect::<Vec<_>>(),
+	})
+}
+
+/// A clip with the name and path of what it plays, times are seconds.
+pub fn cut(project: &PremiereFile, cut: &Cut) -> Value {
+	json!({
+		"id": cut.id,
+		"track": cut.kind.track_name(cut.track),
+		"start": cut.start,
+		"end": cut.end,
+		"in_point": cut.in_point,
+		"out_point": cut.out_point,
+		"source": clip_name(project, cut),
+		"path": match cut.source() {
+			CutSource::Medium(id) => project.medium(*id).map_or(Value::Null, |medium| Value::from(medium.file_path())),
+			CutSource::Sequence(_) => Value::Null,
+		},
+		"speed": cut.speed().rate,
+		"reverse": cut.speed().reverse,
+		"linked": cut.linked(),
+		"grouped": cut.grouped(),
+	})
+}
@@ -0,0 +1,81 @@
+//! Exports sequences to formats other tools understand.
+
+pub mod csv;
+pub mod edl;
+pub mod fcp7;
+pub mod ffmpeg;
+pub mod json;
+pub mod otio;
+
+use crate::premiere::{Cut, CutSource, PremiereSequence, TrackKind};
+use crate::PremiereFile;
+
+/// Formats seconds for text formats, without trailing zeros.
+pub(crate) fn format_seconds(seconds: f64) -> String {
+	let formatted = format!("{:.6}", seconds);
+	let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+	if trimmed.is_empty() || trimmed == "-0" {
+		String::from("0")
+	} else {
+		trimmed.to_owned()
+	}
+}
+
+/// Cuts of every track of a kind ordered by their start,
+/// the index is the one of the track. Tracks without cuts are empty.
+pub(crate) fn tracks(seq: &PremiereSequence, kind: TrackKind) -> Vec<Vec<&Cut>> {
+	let mut tracks: Vec<Vec<&Cut>> = Vec::new();
+	for cut in seq.cuts().iter().filter(|cut| cut.kind == kind) {
+		if tracks.len() <= cut.track {
+			tracks.resize_with(cut.track + 1, Vec::new);
+		}
+		tracks[cut.track].push(cut);
+	}
+	for track in tracks.iter_mut() {
+		track.sort_by(|a, b| a.start.partial_cmp(&b.start).unwrap());
+	}
+	tracks
+}
+
+/// File name of the medium or name of the nested sequence a cut plays.
+pub fn clip_name(project: &PremiereFile, cut: &Cut) -> String {
+	match cut.source() {
+		CutSource::Medium(id) => project.medium(*id).map_or_else(String::new, |medium| medium.file_name().to_owned()),
+		CutSource::Sequence(id) => project.sequences
+			.iter()
+			.find(|seq| seq.id == *id)
+			.map_or_else(|| format!("Sequence {}", id), |seq| seq.name().to_owned())
+	}
+}
+
+/// Path of the medium a cut plays, `None` for nested sequences.
+pub(crate) fn clip_path<'a>(project: &'a PremiereFile, cut: &Cut) -> Option<&'a str> {
+	match cut.source() {
+		CutSource::Medium(id) => project.medium(*id).map(|medium| medium.file_path()),
+		CutSource::Sequence(_) => None
+	}
+}
+
+/// `file://` URL of a local path, Windows paths included.
+pub(crate) fn file_url(path: &str) -> String {
+	let path = path.replace('\\', "/");
+	let mut url = String::from("file://localhost");
+	if !path.starts_with('/') {
+		url.push('/');
+	}
+	for byte in path.bytes() {
+		match byte {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => url.push(byte as char),
+			_ => url.push_str(&format!("%{:02x}", byte))
+		}
+	}
+	url
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+		.replace('\'', "&apos;")
+}
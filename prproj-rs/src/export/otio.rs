@@ -0,0 +1,169 @@
+//! OpenTimelineIO (`.otio`) timelines.
+//!
+//! Every track of the sequence becomes a track of the timeline, empty space
+//! becomes gaps and transitions sit between the clips they join.
+//! Clips which don't play at 100% get a `LinearTimeWarp`, their
+//! `source_range` keeps the duration on the track as OTIO expects.
+
+use serde_json::{json, Value};
+
+use crate::premiere::{Cut, PremiereSequence, TrackKind, Transition};
+use crate::PremiereFile;
+use super::{clip_name, clip_path, file_url, tracks};
+
+/// Transitions OTIO knows by `(match name, transition_type)`,
+/// others become a `Custom_Transition`.
+const TRANSITION_TYPES: [(&str, &str); 1] = [
+	("AE.ADBE Cross Dissolve New", "SMPTE_Dissolve"),
+];
+
+/// Writes the timeline of a sequence as OTIO JSON.
+pub fn write(project: &PremiereFile, seq: &PremiereSequence) -> String {
+	serde_json::to_string_pretty(&timeline(project, seq)).unwrap() + "\n"
+}
+
+/// The `Timeline.1` of a sequence.
+pub fn timeline(project: &PremiereFile, seq: &PremiereSequence) -> Value {
+	let rate = seq.settings().fps();
+	let time = |seconds: f64| rational_time(seconds * rate, rate);
+
+	let mut children: Vec<Value> = Vec::new();
+	for kind in [TrackKind::Video, TrackKind::Audio].iter() {
+		for (index, cuts) in tracks(seq, *kind).into_iter().enumerate() {
+			let transitions: Vec<&Transition> = seq.transitions_on(*kind, index).collect();
+			children.push(json!({
+				"OTIO_SCHEMA": "Track.1",
+				"name": kind.track_name(index),
+				"kind": if *kind == TrackKind::Video { "Video" } else { "Audio" },
+				"source_range": null,
+				"effects": [],
+				"markers": [],
+				"metadata": {},
+				"children": track_items(project, seq, &cuts, &transitions, &time),
+			}));
+		}
+	}
+
+	json!({
+		"OTIO_SCHEMA": "Timeline.1",
+		"name": seq.name(),
+		"global_start_time": time(seq.start_time()),
+		"metadata": {},
+		"tracks": {
+			"OTIO_SCHEMA": "Stack.1",
+			"name": "tracks",
+			"source_range": null,
+			"effects": [],
+			"markers": [],
+			"metadata": {},
+			"children": children,
+		},
+	})
+}
+
+/// Clips of a track with gaps between them and the transitions joining them.
+fn track_items(
+	project: &PremiereFile,
+	seq: &PremiereSequence,
+	cuts: &[&Cut],
+	transitions: &[&Transition],
+	time: &dyn Fn(f64) -> Value
+) -> Vec<Value> {
+	let range = |start: f64, duration: f64| json!({
+		"OTIO_SCHEMA": "TimeRange.1",
+		"start_time": time(start),
+		"duration": time(duration),
+	});
+
+	let mut items: Vec<Value> = Vec::new();
+	let mut position = 0.;
+	for cut in cuts.iter() {
+		// Only transitions between two clips, OTIO has none at the head or tail of a track
+		let joining = transitions.iter().find(|transition| {
+			transition.from_cut.is_some()
+				&& transition.to_cut.and_then(|index| seq.cuts().get(index)).is_some_and(|to| std::ptr::eq(to, *cut))
+		});
+		if let Some(transition) = joining.filter(|_| (cut.start - position).abs() < 1e-6) {
+			items.push(json!({
+				"OTIO_SCHEMA": "Transition.1",
+				"name": transition.display_name,
+				"transition_type": transition_type(transition),
+				"in_offset": time(cut.start - transition.start),
+				"out_offset": time(transition.end - cut.start),
+				"metadata": { "match_name": transition.match_name },
+			}));
+		}
+		if cut.start > position + 1e-6 {
+			items.push(json!({
+				"OTIO_SCHEMA": "Gap.1",
+				"name": "",
+				"source_range": range(0., cut.start - position),
+				"effects": [],
+				"markers": [],
+				"metadata": {},
+			}));
+		}
+		let start = cut.start.max(position);
+		if start >= cut.end {
+			continue;
+		}
+		// Source played after an overlap, scaled by the speed
+		let (from, to) = (cut.source_time_at(start), cut.source_time_at(cut.end));
+		let media_reference = match clip_path(project, cut) {
+			Some(path) => json!({
+				"OTIO_SCHEMA": "ExternalReference.1",
+				"target_url": file_url(path),
+				"available_range": null,
+				"metadata": {},
+			}),
+			None => json!({
+				"OTIO_SCHEMA": "MissingReference.1",
+				"metadata": {},
+			})
+		};
+		items.push(json!({
+			"OTIO_SCHEMA": "Clip.1",
+			"name": clip_name(project, cut),
+			"source_range": range(from.min(to), cut.end - start),
+			"media_reference": media_reference,
+			"effects": time_warp(cut).into_iter().collect::<Vec<_>>(),
+			"markers": [],
+			"metadata": { "premiere_id": cut.id },
+		}));
+		position = cut.end;
+	}
+	items
+}
+
+/// OTIO's `transition_type` of a Premiere transition.
+fn transition_type(transition: &Transition) -> &'static str {
+	TRANSITION_TYPES
+		.iter()
+		.find(|(match_name, _)| *match_name == transition.match_name)
+		.map_or("Custom_Transition", |(_, transition_type)| transition_type)
+}
+
+/// A `LinearTimeWarp` for clips not playing at 100%,
+/// its `time_scalar` is negative when the clip plays in reverse.
+/// Time remapping is approximated with the clip's base speed.
+fn time_warp(cut: &Cut) -> Option<Value> {
+	let speed = cut.speed();
+	if (speed.rate - 1.).abs() < f64::EPSILON && !speed.reverse {
+		return None;
+	}
+	Some(json!({
+		"OTIO_SCHEMA": "LinearTimeWarp.1",
+		"name": "",
+		"effect_name": "LinearTimeWarp",
+		"time_scalar": if speed.reverse { -speed.rate } else { speed.rate },
+		"metadata": {},
+	}))
+}
+
+fn rational_time(value: f64, rate: f64) -> Value {
+	json!({
+		"OTIO_SCHEMA": "RationalTime.1",
+		"rate": rate,
+		"value": value.round(),
+	})
+}
@@ -0,0 +1,216 @@
+//! Deterministic fingerprints of what a sequence plays back, e.g. to tell
+//! whether a cached render of it is still valid.
+//!
+//! The fingerprint is computed from the parsed model with 64-bit FNV-1a over
+//! an explicit little-endian encoding, so it's the same on every platform and
+//! Rust version. It covers the sequence settings, the cuts with their source
+//! ranges, speed and effects, the media they play (nested sequences by their
+//! own fingerprint), the transitions and muted tracks. Names, IDs, marks and
+//! timeline UI state like `TL.SQTrackExpandedHeight` aren't part of it.
+use std::fmt;
+
+use crate::premiere::{
+	Cut, CutSource, Effect, Interpolation, Keyframe, ParamValue,
+	PremiereSequence, TimeInterpolation, TransitionAlignment
+};
+use crate::PremiereFile;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Fingerprint of a sequence, displayed as 16 hex digits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Fingerprint(pub u64);
+
+impl fmt::Display for Fingerprint {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:016x}", self.0)
+	}
+}
+
+/// Fingerprints `sequence` of `project`.
+pub fn fingerprint(project: &PremiereFile, sequence: &PremiereSequence) -> Fingerprint {
+	let mut hasher = Fnv(FNV_OFFSET_BASIS);
+	hasher.sequence(project, sequence, &mut Vec::new());
+	Fingerprint(hasher.0)
+}
+
+struct Fnv(u64);
+
+impl Fnv {
+	fn bytes(&mut self, bytes: &[u8]) {
+		for byte in bytes {
+			self.0 ^= u64::from(*byte);
+			self.0 = self.0.wrapping_mul(FNV_PRIME);
+		}
+	}
+
+	fn u64(&mut self, value: u64) {
+		self.bytes(&value.to_le_bytes());
+	}
+
+	fn f64(&mut self, value: f64) {
+		// -0.0 == 0.0
+		self.u64(if value == 0. { 0 } else { value.to_bits() });
+	}
+
+	fn bool(&mut self, value: bool) {
+		self.bytes(&[value as u8]);
+	}
+
+	fn str(&mut self, value: &str) {
+		self.u64(value.len() as u64);
+		self.bytes(value.as_bytes());
+	}
+
+	/// `visited` holds the IDs of the sequences being fingerprinted,
+	/// to not recurse forever into sequences nested in themselves.
+	fn sequence(&mut self, project: &PremiereFile, seq: &PremiereSequence, visited: &mut Vec<u32>) {
+		visited.push(seq.id);
+		let settings = seq.settings();
+		self.u64(settings.frame_rate);
+		self.u64(u64::from(settings.frame_size.width));
+		self.u64(u64::from(settings.frame_size.height));
+		self.u64(u64::from(settings.pixel_aspect_ratio.0));
+		self.u64(u64::from(settings.pixel_aspect_ratio.1));
+		self.str(&format!("{:?}", settings.field_order));
+		self.u64(u64::from(settings.audio_sample_rate));
+		self.str(&format!("{:?}", settings.audio_channels));
+
+		// Cuts and transitions are written in no particular order
+		let mut cuts: Vec<&Cut> = seq.cuts().iter().collect();
+		cuts.sort_by(|a, b| (a.kind as u8, a.track)
+			.cmp(&(b.kind as u8, b.track))
+			.then(a.start.total_cmp(&b.start))
+			.then(a.end.total_cmp(&b.end)));
+		self.u64(cuts.len() as u64);
+		for cut in cuts {
+			self.cut(project, cut, visited);
+		}
+
+		let mut muted = seq.muted_tracks.clone();
+		muted.sort_by_key(|(kind, track)| (*kind as u8, *track));
+		self.u64(muted.len() as u64);
+		for (kind, track) in muted {
+			self.u64(kind as u64);
+			self.u64(track as u64);
+		}
+
+		let mut transitions: Vec<_> = seq.transitions().iter().collect();
+		transitions.sort_by(|a, b| (a.kind as u8, a.track)
+			.cmp(&(b.kind as u8, b.track))
+			.then(a.start.total_cmp(&b.start)));
+		self.u64(transitions.len() as u64);
+		for transition in transitions {
+			self.u64(transition.kind as u64);
+			self.u64(transition.track as u64);
+			self.f64(transition.start);
+			self.f64(transition.end);
+			self.str(&transition.match_name);
+			self.u64(match transition.alignment {
+				TransitionAlignment::Start => 0,
+				TransitionAlignment::Center => 1,
+				TransitionAlignment::End => 2,
+			});
+		}
+		visited.pop();
+	}
+
+	fn cut(&mut self, project: &PremiereFile, cut: &Cut, visited: &mut Vec<u32>) {
+		self.u64(cut.kind as u64);
+		self.u64(cut.track as u64);
+		self.f64(cut.start);
+		self.f64(cut.end);
+		self.f64(cut.in_point);
+		self.f64(cut.out_point);
+		self.bool(cut.is_disabled());
+
+		match cut.source() {
+			CutSource::Medium(id) => {
+				self.bytes(b"medium");
+				match project.medium(*id) {
+					Some(medium) => {
+						self.str(medium.file_path());
+						self.u64(medium.frame_rate);
+						self.u64(u64::from(medium.size.width));
+						self.u64(u64::from(medium.size.height));
+					}
+					None => self.u64(u64::MAX),
+				}
+			}
+			CutSource::Sequence(id) => {
+				self.bytes(b"sequence");
+				let nested = project.sequences
+					.iter()
+					.find(|seq| seq.id == *id && !visited.contains(id));
+				match nested {
+					Some(nested) => self.sequence(project, nested, visited),
+					None => self.u64(u64::MAX),
+				}
+			}
+		}
+
+		let speed = cut.speed();
+		self.f64(speed.rate);
+		self.bool(speed.reverse);
+		self.u64(match speed.interpolation {
+			TimeInterpolation::FrameSampling => 0,
+			TimeInterpolation::FrameBlending => 1,
+			TimeInterpolation::OpticalFlow => 2,
+		});
+		self.keyframes(&speed.remap);
+
+		self.u64(cut.effects().len() as u64);
+		for effect in cut.effects() {
+			self.effect(effect);
+		}
+	}
+
+	fn effect(&mut self, effect: &Effect) {
+		self.str(&effect.match_name);
+		self.bool(effect.bypass);
+		self.u64(effect.params.len() as u64);
+		for param in effect.params.iter() {
+			self.str(&param.name);
+			self.value(&param.value);
+			self.keyframes(&param.keyframes);
+		}
+	}
+
+	fn keyframes(&mut self, keyframes: &[Keyframe]) {
+		self.u64(keyframes.len() as u64);
+		for keyframe in keyframes {
+			self.f64(keyframe.time);
+			self.value(&keyframe.value);
+			self.u64(match keyframe.interpolation {
+				Interpolation::Linear => 0,
+				Interpolation::Other(code) => u64::from(code),
+			});
+		}
+	}
+
+	fn value(&mut self, value: &ParamValue) {
+		match value {
+			ParamValue::Empty => self.u64(0),
+			ParamValue::Bool(value) => {
+				self.u64(1);
+				self.bool(*value);
+			}
+			ParamValue::Number(number) => {
+				self.u64(2);
+				self.f64(*number);
+			}
+			ParamValue::Point(x, y) => {
+				self.u64(3);
+				self.f64(*x);
+				self.f64(*y);
+			}
+			ParamValue::Text(text) => {
+				self.u64(4);
+				self.str(text);
+			}
+		}
+	}
+}
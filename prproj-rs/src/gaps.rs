@@ -0,0 +1,73 @@
+//! Gaps and flash frames, the most common reasons for a delivery to be rejected.
+use crate::premiere::{Cut, CutPosition, PremiereSequence};
+use crate::timecode::{self, Timecode};
+
+/// A part of the sequence without any visible video,
+/// between its start and the end of the last video clip.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gap {
+	/// Position on the timeline in seconds.
+	pub start: f64,
+	pub end: f64,
+	pub start_timecode: Timecode,
+	pub end_timecode: Timecode,
+	pub frames: i64,
+}
+
+/// A cut too short to be intended, usually left over from trimming.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlashFrame {
+	/// Index into the sequence's cuts.
+	pub cut: usize,
+	pub position: CutPosition,
+	pub frames: i64,
+}
+
+/// Gaps of the sequence's `Timeline`, ordered by start.
+/// Gaps shorter than half a frame are rounding errors and left out.
+/// Without a frame rate there are no frames to count, so none are found.
+pub fn gaps(seq: &PremiereSequence) -> Vec<Gap> {
+	let fps = seq.settings().fps();
+	if fps <= 0. {
+		return Vec::new();
+	}
+	seq.timeline()
+		.gaps(0.)
+		.into_iter()
+		.map(|(start, end)| Gap {
+			start,
+			end,
+			start_timecode: seq.timecode_at(start),
+			end_timecode: seq.timecode_at(end),
+			frames: timecode::to_frames(end - start, fps),
+		})
+		.filter(|gap| gap.frames > 0)
+		.collect()
+}
+
+/// Cuts of every track shorter than `min_frames` frames of the sequence,
+/// ordered by track and start. Like `gaps` none are found without a frame rate.
+pub fn flash_frames(seq: &PremiereSequence, min_frames: u32) -> Vec<FlashFrame> {
+	let fps = seq.settings().fps();
+	if fps <= 0. {
+		return Vec::new();
+	}
+	let frames_of = |cut: &Cut| timecode::to_frames(cut.end - cut.start, fps);
+	let mut cuts: Vec<(usize, &Cut)> = seq.cuts()
+		.iter()
+		.enumerate()
+		.filter(|(_, cut)| frames_of(cut) < i64::from(min_frames))
+		.collect();
+	cuts.sort_by(|(_, a), (_, b)| (a.kind as u8, a.track)
+		.cmp(&(b.kind as u8, b.track))
+		.then(a.start.total_cmp(&b.start)));
+	cuts.into_iter()
+		.map(|(index, cut)| FlashFrame {
+			cut: index,
+			position: CutPosition::new(cut, seq),
+			frames: frames_of(cut),
+		})
+		.collect()
+}
@@ -0,0 +1,127 @@
+//! Versions of a project kept by Premiere's auto-save.
+//!
+//! Premiere periodically writes copies of open projects to its
+//! `Adobe Premiere Pro Auto-Save` folder, named after the project with the
+//! time of saving appended, e.g. `Film-2024-03-05_14-30-00.prproj`.
+//! `History` lists these copies together with the project itself, oldest
+//! first, and parses each one only once it's needed.
+use std::cell::OnceCell;
+use std::fs::{self, File};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::errors::Error;
+use crate::fingerprint::{fingerprint, Fingerprint};
+use crate::{PremiereFile, PremiereReader, Reader};
+
+/// Default name of the auto-save folder, next to the project file.
+pub const AUTO_SAVE_DIR: &str = "Adobe Premiere Pro Auto-Save";
+
+/// The project file or one of its auto-saved copies.
+pub struct ProjectVersion {
+	pub path: PathBuf,
+	/// When the file was last written.
+	pub modified: SystemTime,
+	/// Whether this is a copy in the auto-save folder rather than the project itself.
+	pub auto_save: bool,
+	project: OnceCell<PremiereFile>,
+}
+
+impl ProjectVersion {
+	fn new(path: PathBuf, auto_save: bool) -> Result<Self, Error> {
+		let modified = fs::metadata(&path)?.modified()?;
+		Ok(Self {
+			path,
+			modified,
+			auto_save,
+			project: OnceCell::new(),
+		})
+	}
+
+	/// Reads and parses the file the first time it's called.
+	pub fn project(&self) -> Result<&PremiereFile, Error> {
+		if let Some(project) = self.project.get() {
+			return Ok(project);
+		}
+		let mut reader = PremiereReader::from_reader(File::open(&self.path)?)?;
+		reader.read()?;
+		Ok(self.project.get_or_init(|| reader.take()))
+	}
+
+	/// `Fingerprint` of the sequence with the given `ObjectUID`,
+	/// `None` if this version doesn't have it.
+	pub fn sequence_fingerprint(&self, uid: &str) -> Result<Option<Fingerprint>, Error> {
+		let project = self.project()?;
+		Ok(project.sequences
+			.iter()
+			.find(|seq| seq.uid == uid)
+			.map(|seq| fingerprint(project, seq)))
+	}
+}
+
+/// All versions of a project, oldest first.
+pub struct History {
+	versions: Vec<ProjectVersion>,
+}
+
+impl History {
+	/// Lists `project` and the `.prproj` files in `auto_save_dir` named after it.
+	/// Nothing is parsed yet. A project which was never auto-saved
+	/// has no `auto_save_dir`, its history is the project alone.
+	pub fn new(project: &Path, auto_save_dir: &Path) -> Result<Self, Error> {
+		let prefix = project
+			.file_stem()
+			.map(|stem| format!("{}-", stem.to_string_lossy()))
+			.unwrap_or_default();
+		let mut versions = vec![ProjectVersion::new(project.to_owned(), false)?];
+		let entries = match fs::read_dir(auto_save_dir) {
+			Ok(entries) => entries,
+			Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Self { versions }),
+			Err(err) => return Err(err.into()),
+		};
+		for entry in entries {
+			let path = entry?.path();
+			let is_copy = path.extension().is_some_and(|ext| ext == "prproj")
+				&& path.file_stem().is_some_and(|stem| stem.to_string_lossy().starts_with(&prefix));
+			if is_copy {
+				versions.push(ProjectVersion::new(path, true)?);
+			}
+		}
+		versions.sort_by(|a, b| a.modified.cmp(&b.modified).then_with(|| a.path.cmp(&b.path)));
+		Ok(Self { versions })
+	}
+
+	pub fn versions(&self) -> &[ProjectVersion] {
+		&self.versions
+	}
+
+	pub fn latest(&self) -> &ProjectVersion {
+		// `new` always adds the project itself
+		self.versions.last().unwrap()
+	}
+
+	/// The first version in which the sequence plays back like it does in the latest one,
+	/// found by comparing `ProjectVersion::sequence_fingerprint`s from newest to oldest.
+	/// Like the fingerprint this ignores e.g. renaming the sequence or moving its marks.
+	/// `sequence` is the name or `ObjectUID` of a sequence of the latest version,
+	/// returns `None` if there's no such sequence. If it never changed,
+	/// that's the oldest version which has it.
+	/// Only the versions up to the change are parsed.
+	pub fn last_changed(&self, sequence: &str) -> Result<Option<&ProjectVersion>, Error> {
+		let latest = self.latest().project()?;
+		let uid = match latest.sequences.iter().find(|seq| seq.uid == sequence || seq.name == sequence) {
+			Some(seq) => seq.uid.to_owned(),
+			None => return Ok(None),
+		};
+		let latest_fingerprint = self.latest().sequence_fingerprint(&uid)?;
+		let mut changed = self.latest();
+		for version in self.versions.iter().rev().skip(1) {
+			if version.sequence_fingerprint(&uid)? != latest_fingerprint {
+				break;
+			}
+			changed = version;
+		}
+		Ok(Some(changed))
+	}
+}
@@ -2,10 +2,7 @@
 macro_rules! sorted_vec {
     ($($x:expr),*) => {
 		{
-			let mut temp_vec = Vec::new();
-			$(
-				temp_vec.push($x);
-			)*
+			let mut temp_vec = vec![$($x),*];
 			temp_vec.sort();
 			temp_vec
 		}
@@ -23,14 +20,24 @@ pub mod wasm_utils;
 
 pub mod premiere;
 pub use premiere::{
-	PremiereMedia, PremiereMedium,
+	MediumId, PremiereMedia, PremiereMedium,
 	PremiereSequence, PremiereSequences
 };
 pub use premiere::reader::{PremiereReader, Reader, PremiereFile};
 
 pub mod errors;
+pub mod decompress;
 pub mod timeline;
 pub mod element;
+pub mod export;
+pub mod diff;
+pub mod merge;
+pub mod history;
+pub mod fingerprint;
+pub mod lint;
+pub mod gaps;
+pub mod scan;
+pub mod timecode;
 
 const TICKS_PER_SECOND: u64 = 254_016_000_000;
 
@@ -0,0 +1,285 @@
+//! Checks a parsed project for common mistakes before delivery.
+//!
+//! Each `Rule` can be turned off and some take parameters, see `LintConfig`.
+//! Issues are reported in the order of the rules, then of the sequences.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::premiere::{Cut, CutSource, PremiereSequence, TrackKind};
+use crate::timecode::{self, Timecode};
+use crate::{gaps, PremiereFile};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Rule {
+	/// A clip plays a file which doesn't exist at its path on this machine.
+	OfflineMedia,
+	/// A video clip's medium has another frame rate than its sequence.
+	FrameRateMismatch,
+	/// No video is visible between the start of the sequence and its last video clip.
+	Gap,
+	/// A clip on V1 is at most `LintConfig::flash_frames` frames long.
+	FlashFrame,
+	/// A clip is turned off and won't be rendered.
+	DisabledClip,
+	/// A track of a final sequence is muted or its output is turned off.
+	MutedTrack,
+	/// Several sequences have the same name.
+	DuplicateSequenceName,
+}
+
+impl Rule {
+	pub const ALL: [Rule; 7] = [
+		Rule::OfflineMedia,
+		Rule::FrameRateMismatch,
+		Rule::Gap,
+		Rule::FlashFrame,
+		Rule::DisabledClip,
+		Rule::MutedTrack,
+		Rule::DuplicateSequenceName,
+	];
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Rule::OfflineMedia => "offline_media",
+			Rule::FrameRateMismatch => "frame_rate_mismatch",
+			Rule::Gap => "gap",
+			Rule::FlashFrame => "flash_frame",
+			Rule::DisabledClip => "disabled_clip",
+			Rule::MutedTrack => "muted_track",
+			Rule::DuplicateSequenceName => "duplicate_sequence_name",
+		}
+	}
+
+	pub fn severity(self) -> Severity {
+		match self {
+			Rule::OfflineMedia | Rule::Gap | Rule::FlashFrame => Severity::Error,
+			_ => Severity::Warning,
+		}
+	}
+}
+
+impl fmt::Display for Rule {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl FromStr for Rule {
+	type Err = String;
+
+	/// Parses the `name` of a rule, dashes may be used instead of underscores.
+	fn from_str(name: &str) -> Result<Self, Self::Err> {
+		let name = name.replace('-', "_");
+		Rule::ALL
+			.iter()
+			.copied()
+			.find(|rule| rule.name() == name)
+			.ok_or_else(|| format!("unknown rule \"{}\"", name))
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Severity {
+	Warning,
+	Error,
+}
+
+impl fmt::Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Severity::Warning => "warning",
+			Severity::Error => "error",
+		})
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct LintConfig {
+	/// Rules to check, all of them by default.
+	pub rules: Vec<Rule>,
+	/// Clips on V1 this many frames long or shorter are flash frames.
+	pub flash_frames: u32,
+	/// Sequences whose name contains this, ignoring case, are final
+	/// and checked by `Rule::MutedTrack`.
+	pub final_sequences: String,
+}
+
+impl Default for LintConfig {
+	fn default() -> Self {
+		Self {
+			rules: Rule::ALL.to_vec(),
+			flash_frames: 1,
+			final_sequences: "final".to_owned(),
+		}
+	}
+}
+
+/// Something `lint` found.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Issue {
+	pub rule: Rule,
+	pub severity: Severity,
+	/// Name of the sequence, `None` for issues of the whole project.
+	pub sequence: Option<String>,
+	/// Where on the timeline, if the issue is about a range of it.
+	pub start: Option<Timecode>,
+	pub end: Option<Timecode>,
+	pub message: String,
+}
+
+impl Issue {
+	fn new(rule: Rule, sequence: Option<&PremiereSequence>, message: String) -> Self {
+		Self {
+			rule,
+			severity: rule.severity(),
+			sequence: sequence.map(|seq| seq.name.to_owned()),
+			start: None,
+			end: None,
+			message,
+		}
+	}
+
+	fn at(mut self, seq: &PremiereSequence, start: f64, end: f64) -> Self {
+		self.start = Some(seq.timecode_at(start));
+		self.end = Some(seq.timecode_at(end));
+		self
+	}
+}
+
+impl fmt::Display for Issue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}[{}]", self.severity, self.rule)?;
+		if let Some(sequence) = &self.sequence {
+			write!(f, " \"{}\"", sequence)?;
+		}
+		if let (Some(start), Some(end)) = (&self.start, &self.end) {
+			write!(f, " {}-{}", start, end)?;
+		}
+		write!(f, ": {}", self.message)
+	}
+}
+
+/// Checks `project` with the rules of `config`.
+pub fn lint(project: &PremiereFile, config: &LintConfig) -> Vec<Issue> {
+	let mut issues = Vec::new();
+	for rule in Rule::ALL.iter().copied().filter(|rule| config.rules.contains(rule)) {
+		if rule == Rule::DuplicateSequenceName {
+			duplicate_names(project, &mut issues);
+			continue;
+		}
+		for seq in project.sequences.iter() {
+			match rule {
+				Rule::OfflineMedia => offline_media(project, seq, &mut issues),
+				Rule::FrameRateMismatch => frame_rate_mismatches(project, seq, &mut issues),
+				Rule::Gap => video_gaps(seq, &mut issues),
+				Rule::FlashFrame => flash_frames(seq, config.flash_frames, &mut issues),
+				Rule::DisabledClip => disabled_clips(seq, &mut issues),
+				Rule::MutedTrack => muted_tracks(seq, &config.final_sequences, &mut issues),
+				Rule::DuplicateSequenceName => unreachable!(),
+			}
+		}
+	}
+	issues
+}
+
+/// Media of the sequence's cuts, each one once.
+fn media_of<'a>(
+	project: &'a PremiereFile,
+	seq: &'a PremiereSequence,
+	kind: Option<TrackKind>
+) -> Vec<(&'a Cut, &'a str, u64)> {
+	let mut media: Vec<(&Cut, &str, u64)> = Vec::new();
+	for cut in seq.cuts().iter().filter(|cut| kind.is_none_or(|kind| cut.kind == kind)) {
+		let medium = match cut.source() {
+			CutSource::Medium(id) => project.medium(*id),
+			CutSource::Sequence(_) => None,
+		};
+		if let Some(medium) = medium {
+			if !media.iter().any(|(_, path, _)| *path == medium.file_path()) {
+				media.push((cut, medium.file_path(), medium.frame_rate));
+			}
+		}
+	}
+	media
+}
+
+fn offline_media(project: &PremiereFile, seq: &PremiereSequence, issues: &mut Vec<Issue>) {
+	for (cut, path, _) in media_of(project, seq, None) {
+		if !Path::new(path).exists() {
+			issues.push(Issue::new(Rule::OfflineMedia, Some(seq), format!("{} is offline", path))
+				.at(seq, cut.start, cut.end));
+		}
+	}
+}
+
+fn frame_rate_mismatches(project: &PremiereFile, seq: &PremiereSequence, issues: &mut Vec<Issue>) {
+	let frame_rate = seq.settings().frame_rate;
+	for (cut, path, medium_rate) in media_of(project, seq, Some(TrackKind::Video)) {
+		if medium_rate != 0 && frame_rate != 0 && medium_rate != frame_rate {
+			let message = format!(
+				"{} is {:.3} fps, the sequence {:.3} fps",
+				path,
+				timecode::fps(medium_rate),
+				timecode::fps(frame_rate)
+			);
+			issues.push(Issue::new(Rule::FrameRateMismatch, Some(seq), message).at(seq, cut.start, cut.end));
+		}
+	}
+}
+
+/// Parts of the sequence without visible video, clips on higher tracks
+/// filling gaps on V1.
+fn video_gaps(seq: &PremiereSequence, issues: &mut Vec<Issue>) {
+	for gap in gaps::gaps(seq) {
+		let message = format!("gap of {} frames without video", gap.frames);
+		issues.push(Issue::new(Rule::Gap, Some(seq), message).at(seq, gap.start, gap.end));
+	}
+}
+
+fn flash_frames(seq: &PremiereSequence, max_frames: u32, issues: &mut Vec<Issue>) {
+	for flash_frame in gaps::flash_frames(seq, max_frames + 1) {
+		let on_v1 = seq.cuts()
+			.get(flash_frame.cut)
+			.is_some_and(|cut| cut.kind == TrackKind::Video && cut.track == 0);
+		if on_v1 {
+			let position = flash_frame.position;
+			let message = format!("clip of {} frames on V1", flash_frame.frames);
+			issues.push(Issue::new(Rule::FlashFrame, Some(seq), message).at(seq, position.start, position.end));
+		}
+	}
+}
+
+fn disabled_clips(seq: &PremiereSequence, issues: &mut Vec<Issue>) {
+	for cut in seq.cuts().iter().filter(|cut| cut.is_disabled()) {
+		let message = format!("clip on {} is disabled", cut.kind.track_name(cut.track));
+		issues.push(Issue::new(Rule::DisabledClip, Some(seq), message).at(seq, cut.start, cut.end));
+	}
+}
+
+fn muted_tracks(seq: &PremiereSequence, final_sequences: &str, issues: &mut Vec<Issue>) {
+	if !seq.name.to_lowercase().contains(&final_sequences.to_lowercase()) {
+		return;
+	}
+	for (kind, track) in seq.muted_tracks.iter() {
+		let message = format!("{} is muted", kind.track_name(*track));
+		issues.push(Issue::new(Rule::MutedTrack, Some(seq), message));
+	}
+}
+
+fn duplicate_names(project: &PremiereFile, issues: &mut Vec<Issue>) {
+	let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+	for seq in project.sequences.iter() {
+		*counts.entry(seq.name.as_str()).or_default() += 1;
+	}
+	for (name, count) in counts.into_iter().filter(|(_, count)| *count > 1) {
+		let message = format!("{} sequences are named \"{}\"", count, name);
+		issues.push(Issue::new(Rule::DuplicateSequenceName, None, message));
+	}
+}
@@ -0,0 +1,468 @@
+//! Three-way merge of two copies of a project branched off the same one.
+//!
+//! The merged project is "ours" with the changes of "theirs" applied:
+//! sequences, bins and clips added in theirs are copied over together with
+//! everything they reference, and sequences edited only in theirs replace
+//! our version along with their tracks and clips. Objects are copied by `ObjectUID`
+//! when ours doesn't have them yet, objects referenced by `ObjectID` get new IDs.
+//!
+//! A sequence edited differently in both copies, or edited in one and
+//! deleted in the other, is a conflict and keeps our version.
+//! Deletions made only in theirs are not applied.
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, Write};
+
+use libflate::gzip;
+
+use crate::diff::{self, Change};
+use crate::element::{Element, ElementGetExt};
+use crate::errors::Error;
+use crate::premiere::PremiereSequence;
+use crate::{PremiereFile, PremiereReader, Reader};
+
+/// A sequence which can't be merged automatically.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Conflict {
+	/// `ObjectUID` of the sequence.
+	pub uid: String,
+	/// Name of the sequence in the base project.
+	pub sequence: String,
+	/// Changes since the base, `SequenceRemoved` when it was deleted.
+	pub ours: Vec<Change>,
+	pub theirs: Vec<Change>,
+}
+
+impl fmt::Display for Conflict {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "conflict in sequence \"{}\"", self.sequence)?;
+		for change in self.ours.iter() {
+			writeln!(f, "  ours:   {}", change)?;
+		}
+		for change in self.theirs.iter() {
+			writeln!(f, "  theirs: {}", change)?;
+		}
+		Ok(())
+	}
+}
+
+pub struct Merge {
+	/// XML of the merged project.
+	pub project: Element,
+	/// Changes of theirs which were applied to ours.
+	pub applied: Vec<Change>,
+	pub conflicts: Vec<Conflict>,
+}
+
+impl Merge {
+	pub fn write_xml<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		self.project
+			.write_to(writer)
+			.map_err(|err| io::Error::other(err.to_string()))
+	}
+
+	/// Writes the merged project gzipped, the way Premiere saves it.
+	pub fn write_gzip<W: Write>(&self, writer: W) -> io::Result<()> {
+		let mut encoder = gzip::Encoder::new(writer)?;
+		self.write_xml(&mut encoder)?;
+		encoder.finish().into_result()?;
+		Ok(())
+	}
+}
+
+/// Merges the changes made in `theirs` since `base` into `ours`.
+/// The projects are the contents of `.prproj` files, gzipped or not.
+pub fn merge(base: &[u8], ours: &[u8], theirs: &[u8]) -> Result<Merge, Error> {
+	let (_, base) = read(base)?;
+	let (ours_root, ours) = read(ours)?;
+	let (theirs_root, theirs) = read(theirs)?;
+
+	let mut merger = Merger::new(ours_root, &theirs_root);
+	let mut applied = Vec::new();
+	let mut conflicts = Vec::new();
+
+	for their_seq in theirs.sequences.iter() {
+		let base_seq = find_sequence(&base, &their_seq.uid);
+		let our_seq = find_sequence(&ours, &their_seq.uid);
+		match (base_seq, our_seq) {
+			(None, None) => {
+				merger.import(&their_seq.uid);
+				applied.push(Change::SequenceAdded {
+					uid: their_seq.uid.to_owned(),
+					name: their_seq.name.to_owned(),
+				});
+			}
+			// Added in both, ours wins
+			(None, Some(_)) => {}
+			(Some(base_seq), Some(our_seq)) => {
+				let their_changes = changes(&base, base_seq, &theirs, their_seq);
+				let our_changes = changes(&base, base_seq, &ours, our_seq);
+				if their_changes.is_empty() || their_changes == our_changes {
+					continue;
+				}
+				if our_changes.is_empty() {
+					merger.replace(&their_seq.uid);
+					applied.extend(their_changes);
+				} else {
+					conflicts.push(Conflict {
+						uid: their_seq.uid.to_owned(),
+						sequence: base_seq.name.to_owned(),
+						ours: our_changes,
+						theirs: their_changes,
+					});
+				}
+			}
+			(Some(base_seq), None) => {
+				let their_changes = changes(&base, base_seq, &theirs, their_seq);
+				if !their_changes.is_empty() {
+					conflicts.push(Conflict {
+						uid: their_seq.uid.to_owned(),
+						sequence: base_seq.name.to_owned(),
+						ours: vec![removed(base_seq)],
+						theirs: their_changes,
+					});
+				}
+			}
+		}
+	}
+	for our_seq in ours.sequences.iter() {
+		let base_seq = match find_sequence(&base, &our_seq.uid) {
+			Some(base_seq) => base_seq,
+			None => continue
+		};
+		if find_sequence(&theirs, &our_seq.uid).is_none() {
+			let our_changes = changes(&base, base_seq, &ours, our_seq);
+			if !our_changes.is_empty() {
+				conflicts.push(Conflict {
+					uid: our_seq.uid.to_owned(),
+					sequence: base_seq.name.to_owned(),
+					ours: our_changes,
+					theirs: vec![removed(base_seq)],
+				});
+			}
+		}
+	}
+
+	// Items are added to the bin they're in, a new bin brings its contents along
+	for item in theirs.items.iter() {
+		let is_new = |file: &PremiereFile| !file.items.iter().any(|found| found.uid == item.uid);
+		if !is_new(&base) || !is_new(&ours) {
+			continue;
+		}
+		merger.add_to_bin(&item.uid);
+		applied.push(Change::ItemAdded {
+			uid: item.uid.to_owned(),
+			name: item.name.to_owned(),
+			bin: item.bin_path(),
+		});
+	}
+	for medium in theirs.media.iter() {
+		let is_new = |file: &PremiereFile| !file.media.iter().any(|found| found.uid() == medium.uid());
+		if is_new(&base) && is_new(&ours) && merger.uids.contains(medium.uid()) {
+			applied.push(Change::MediumAdded {
+				uid: medium.uid().to_owned(),
+				path: medium.file_path().to_owned(),
+			});
+		}
+	}
+
+	Ok(Merge {
+		project: merger.project,
+		applied,
+		conflicts,
+	})
+}
+
+fn read(project: &[u8]) -> Result<(Element, PremiereFile), Error> {
+	let mut reader = PremiereReader::from_reader(project)?;
+	reader.read()?;
+	let root = reader.root().clone();
+	Ok((root, reader.take()))
+}
+
+fn find_sequence<'a>(file: &'a PremiereFile, uid: &str) -> Option<&'a PremiereSequence> {
+	file.sequences.iter().find(|seq| seq.uid == uid)
+}
+
+fn changes(
+	base: &PremiereFile,
+	base_seq: &PremiereSequence,
+	file: &PremiereFile,
+	seq: &PremiereSequence,
+) -> Vec<Change> {
+	let mut changes = Vec::new();
+	diff::diff_sequence(base, base_seq, file, seq, &mut changes);
+	changes
+}
+
+fn removed(seq: &PremiereSequence) -> Change {
+	Change::SequenceRemoved {
+		uid: seq.uid.to_owned(),
+		name: seq.name.to_owned(),
+	}
+}
+
+/// A reference from one object of the project to another.
+enum Ref {
+	Id(String),
+	Uid(String),
+}
+
+/// Copies objects of theirs into our project.
+struct Merger<'a> {
+	project: Element,
+	theirs_by_id: HashMap<&'a str, &'a Element>,
+	theirs_by_uid: HashMap<&'a str, &'a Element>,
+	/// `ObjectUID`s present in the merged project.
+	uids: HashSet<String>,
+	/// Maps `ObjectID`s of theirs to the ones given to their copies.
+	ids: HashMap<String, String>,
+	next_id: u64,
+}
+
+impl<'a> Merger<'a> {
+	fn new(project: Element, theirs: &'a Element) -> Self {
+		let mut theirs_by_id = HashMap::new();
+		let mut theirs_by_uid = HashMap::new();
+		for child in theirs.children() {
+			if let Some(id) = child.attr("ObjectID") {
+				theirs_by_id.insert(id, child);
+			}
+			if let Some(uid) = child.attr("ObjectUID") {
+				theirs_by_uid.insert(uid, child);
+			}
+		}
+		let uids = project
+			.children()
+			.filter_map(|child| child.attr("ObjectUID"))
+			.map(str::to_owned)
+			.collect();
+		let next_id = project
+			.children()
+			.filter_map(|child| child.attr("ObjectID"))
+			.filter_map(|id| id.parse::<u64>().ok())
+			.max()
+			.unwrap_or(0) + 1;
+		Self {
+			project,
+			theirs_by_id,
+			theirs_by_uid,
+			uids,
+			ids: HashMap::new(),
+			next_id,
+		}
+	}
+
+	/// Copies the object with the `ObjectUID` `uid` and everything
+	/// it references which the project doesn't have yet.
+	fn import(&mut self, uid: &str) {
+		let mut copies = Vec::new();
+		let mut stack = vec![Ref::Uid(uid.to_owned())];
+		while let Some(reference) = stack.pop() {
+			let elem = match reference {
+				Ref::Uid(uid) => {
+					if self.uids.contains(&uid) {
+						continue;
+					}
+					match self.theirs_by_uid.get(uid.as_str()) {
+						Some(elem) => {
+							self.uids.insert(uid);
+							*elem
+						}
+						None => continue
+					}
+				}
+				Ref::Id(id) => {
+					if self.ids.contains_key(&id) {
+						continue;
+					}
+					match self.theirs_by_id.get(id.as_str()) {
+						Some(elem) => {
+							self.ids.insert(id, self.next_id.to_string());
+							self.next_id += 1;
+							*elem
+						}
+						None => continue
+					}
+				}
+			};
+			collect_refs(elem, &mut stack);
+			copies.push(elem.clone());
+		}
+		for mut copy in copies {
+			rewrite_ids(&mut copy, &self.ids);
+			if copy.name() == "Sequence" {
+				self.renumber_sequence(&mut copy);
+			}
+			self.project.append_child(copy);
+		}
+	}
+
+	/// Swaps our version of the sequence with the `ObjectUID` `uid` for theirs,
+	/// along with its tracks and the objects only they refer to, see `parts_of`.
+	fn replace(&mut self, uid: &str) {
+		let (uids, ids) = self.parts_of(uid);
+
+		let mut project = Element::bare(self.project.name());
+		for (name, value) in self.project.attrs() {
+			project.set_attr(name, value);
+		}
+		for node in self.project.nodes() {
+			let is_replaced = node.as_element().is_some_and(|elem|
+				elem.attr("ObjectUID").is_some_and(|found| uids.contains(found))
+					|| elem.attr("ObjectID").is_some_and(|found| ids.contains(found)));
+			if !is_replaced {
+				project.append_node(node.clone());
+			}
+		}
+		self.project = project;
+		for uid in uids.iter() {
+			self.uids.remove(uid);
+		}
+		self.import(uid);
+	}
+
+	/// Our version of the sequence `uid`: the `ObjectUID`s of the sequence and
+	/// its tracks, and the `ObjectID`s of the objects they reach (track groups,
+	/// clip items, their clips...) which nothing else refers to. Objects shared
+	/// with the rest of the project, e.g. the markers of a master clip, are left out.
+	fn parts_of(&self, uid: &str) -> (HashSet<String>, HashSet<String>) {
+		let mut by_id = HashMap::new();
+		let mut by_uid = HashMap::new();
+		for child in self.project.children() {
+			if let Some(id) = child.attr("ObjectID") {
+				by_id.insert(id, child);
+			}
+			if let Some(uid) = child.attr("ObjectUID") {
+				by_uid.insert(uid, child);
+			}
+		}
+
+		// Unlike media or nested sequences the tracks belong to the sequence
+		let mut uids = HashSet::new();
+		let mut ids = HashSet::new();
+		let mut stack = vec![Ref::Uid(uid.to_owned())];
+		while let Some(reference) = stack.pop() {
+			let elem = match reference {
+				Ref::Id(id) => match by_id.get(id.as_str()) {
+					Some(elem) if ids.insert(id) => elem,
+					_ => continue
+				},
+				Ref::Uid(found) => match by_uid.get(found.as_str()) {
+					Some(elem) if (found == uid || elem.name().ends_with("Track")) && uids.insert(found) => elem,
+					_ => continue
+				},
+			};
+			collect_refs(elem, &mut stack);
+		}
+
+		let mut shared = HashSet::new();
+		let mut stack = Vec::new();
+		for child in self.project.children() {
+			let is_part = child.attr("ObjectID").is_some_and(|id| ids.contains(id))
+				|| child.attr("ObjectUID").is_some_and(|uid| uids.contains(uid));
+			if !is_part {
+				collect_refs(child, &mut stack);
+			}
+		}
+		while let Some(reference) = stack.pop() {
+			if let Ref::Id(id) = reference {
+				if let Some(elem) = by_id.get(id.as_str()).filter(|_| ids.contains(&id)) {
+					if shared.insert(id) {
+						collect_refs(elem, &mut stack);
+					}
+				}
+			}
+		}
+		ids.retain(|id| !shared.contains(id));
+		(uids, ids)
+	}
+
+	/// Imports the project item `uid` and lists it in the bin which holds it in theirs.
+	/// Items in bins which are new themselves come along with their bin.
+	fn add_to_bin(&mut self, uid: &str) {
+		if self.uids.contains(uid) {
+			return;
+		}
+		let bin = match self.theirs_by_uid.values().find(|elem| bin_items(elem)
+			.is_some_and(|items| items.children().any(|item| item.attr("ObjectURef") == Some(uid)))) {
+			Some(bin) => bin.attr("ObjectUID").unwrap_or_default().to_owned(),
+			None => return
+		};
+		self.import(uid);
+		let bin = self.project
+			.children_mut()
+			.find(|elem| elem.attr("ObjectUID") == Some(bin.as_str()) && bin_items(elem).is_some());
+		let bin = match bin {
+			Some(bin) => bin,
+			// The bin doesn't exist in ours, the item ends up unlisted
+			None => return
+		};
+		let items = bin
+			.children_mut()
+			.find(|child| child.name() == "ProjectItemContainer")
+			.and_then(|container| container.children_mut().find(|child| child.name() == "Items"));
+		if let Some(items) = items {
+			let index = items.children().count();
+			items.append_child(
+				Element::builder("Item")
+					.attr("Index", index.to_string())
+					.attr("ObjectURef", uid)
+					.build()
+			);
+		}
+	}
+
+	/// Gives an imported sequence a new `<ID>` if one of ours already uses it.
+	fn renumber_sequence(&self, seq: &mut Element) {
+		let ids: Vec<u64> = self.project
+			.children()
+			.filter(|elem| elem.name() == "Sequence" && elem.attr("ObjectUID") != seq.attr("ObjectUID"))
+			.filter_map(|elem| elem.get("ID").ok())
+			.filter_map(|id| id.text().trim().parse().ok())
+			.collect();
+		let id = match seq.get("ID").ok().and_then(|id| id.text().trim().parse::<u64>().ok()) {
+			Some(id) => id,
+			None => return
+		};
+		if !ids.contains(&id) {
+			return;
+		}
+		let free = ids.iter().max().unwrap_or(&0) + 1;
+		if let Some(id_elem) = seq.children_mut().find(|child| child.name() == "ID") {
+			for text in id_elem.texts_mut() {
+				*text = free.to_string();
+			}
+		}
+	}
+}
+
+/// `<Items>` of a `RootProjectItem` or `BinProjectItem`.
+fn bin_items(elem: &Element) -> Option<&Element> {
+	elem.get("ProjectItemContainer").ok()?.get("Items").ok()
+}
+
+fn collect_refs(elem: &Element, refs: &mut Vec<Ref>) {
+	for child in elem.children() {
+		if let Some(id) = child.attr("ObjectRef") {
+			refs.push(Ref::Id(id.to_owned()));
+		}
+		if let Some(uid) = child.attr("ObjectURef") {
+			refs.push(Ref::Uid(uid.to_owned()));
+		}
+		collect_refs(child, refs);
+	}
+}
+
+fn rewrite_ids(elem: &mut Element, ids: &HashMap<String, String>) {
+	for name in ["ObjectID", "ObjectRef"].iter() {
+		let new_id = elem.attr(name).and_then(|id| ids.get(id)).cloned();
+		if let Some(new_id) = new_id {
+			elem.set_attr(*name, new_id);
+		}
+	}
+	for child in elem.children_mut() {
+		rewrite_ids(child, ids);
+	}
+}
@@ -0,0 +1,129 @@
+//! Reading projects from an `AsyncRead`, e.g. an upload to a tokio server,
+//! enabled with the `async` feature.
+//!
+//! The gzip stream is inflated and the XML tokenized as the data arrives,
+//! every few thousand XML events other tasks get a chance to run.
+//! Resolving the parsed elements into a `PremiereFile` works on what's
+//! already in memory, like `Reader::read`.
+//!
+//! minidom can only parse from a blocking reader, so the tree is built here
+//! without it. Premiere doesn't use XML namespaces and neither does this
+//! builder: prefixed names and `xmlns` declarations are rejected with
+//! `minidom::Error::InvalidElement` rather than read differently from the
+//! blocking reader.
+use std::io::Cursor;
+
+use async_compression::tokio::bufread::GzipDecoder;
+use quick_xml_async::events::{BytesStart, Event};
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncReadExt, BufReader};
+
+use crate::decompress;
+use crate::element::Element;
+use crate::errors::Error;
+use crate::{PremiereFile, PremiereReader, Reader};
+
+/// XML events parsed before yielding to the executor.
+const EVENTS_PER_YIELD: usize = 4096;
+
+impl PremiereReader {
+	/// Parses a project, decompressing it on the fly when it's gzipped.
+	/// The async counterpart of `from_reader`.
+	pub async fn from_async_reader<R: AsyncRead + Unpin>(mut source: R) -> Result<Self, Error> {
+		// Peek at the magic bytes, putting them back in front
+		let mut magic = [0; 2];
+		let mut peeked = 0;
+		while peeked < magic.len() {
+			match source.read(&mut magic[peeked..]).await? {
+				0 => break,
+				read => peeked += read,
+			}
+		}
+		let source = Cursor::new(magic).take(peeked as u64).chain(source);
+		let compressed = decompress::is_gzip(&magic[..peeked]);
+		let root = if compressed {
+			parse(BufReader::new(GzipDecoder::new(BufReader::new(source)))).await?
+		} else {
+			parse(BufReader::new(source)).await?
+		};
+		Ok(Self::with_root(root, compressed))
+	}
+}
+
+/// Parses and reads a project, the async counterpart of
+/// `PremiereReader::from_reader` followed by `read` and `take`.
+pub async fn read<R: AsyncRead + Unpin>(source: R) -> Result<PremiereFile, Error> {
+	let mut reader = PremiereReader::from_async_reader(source).await?;
+	reader.read()?;
+	Ok(reader.take())
+}
+
+/// Builds the tree of elements the way `minidom` does, keeping
+/// whitespace between elements as text nodes. Namespaced elements aren't
+/// supported.
+async fn parse<R: AsyncBufRead + Unpin>(source: R) -> Result<Element, Error> {
+	let mut reader = quick_xml_async::Reader::from_reader(source);
+	let mut buf = Vec::new();
+	let mut open: Vec<Element> = Vec::new();
+	let mut events = 0;
+	loop {
+		let closed = match reader.read_event_into_async(&mut buf).await? {
+			Event::Start(start) => {
+				open.push(element(&start)?);
+				None
+			}
+			Event::Empty(empty) => Some(element(&empty)?),
+			Event::End(_) => open.pop(),
+			Event::Text(text) => {
+				let text = text.unescape()?;
+				if let (Some(parent), false) = (open.last_mut(), text.is_empty()) {
+					parent.append_text_node(text);
+				}
+				None
+			}
+			Event::CData(cdata) => {
+				let text = cdata.decode()
+					.map_err(|err| Error::AsyncXml(quick_xml_async::Error::Encoding(err)))?;
+				if let (Some(parent), false) = (open.last_mut(), text.is_empty()) {
+					parent.append_text_node(text);
+				}
+				None
+			}
+			Event::Eof => return Err(Error::Xml(minidom::Error::EndOfDocument)),
+			_ => None,
+		};
+		if let Some(closed) = closed {
+			match open.last_mut() {
+				Some(parent) => parent.append_child(closed),
+				None => return Ok(closed),
+			};
+		}
+
+		buf.clear();
+		events += 1;
+		if events % EVENTS_PER_YIELD == 0 {
+			tokio::task::yield_now().await;
+		}
+	}
+}
+
+fn element(start: &BytesStart) -> Result<Element, Error> {
+	let utf8 = |bytes: &[u8]| std::str::from_utf8(bytes)
+		.map(str::to_owned)
+		.map_err(minidom::Error::Utf8Error);
+
+	let namespaced = |name: &[u8]| name.contains(&b':') || name == b"xmlns";
+
+	if namespaced(start.name().as_ref()) {
+		return Err(Error::Xml(minidom::Error::InvalidElement));
+	}
+	let mut builder = Element::builder(utf8(start.name().as_ref())?);
+	for attribute in start.attributes() {
+		let attribute = attribute.map_err(quick_xml_async::Error::from)?;
+		if namespaced(attribute.key.as_ref()) {
+			return Err(Error::Xml(minidom::Error::InvalidElement));
+		}
+		let value = attribute.unescape_value()?.into_owned();
+		builder = builder.attr(utf8(attribute.key.as_ref())?, value);
+	}
+	Ok(builder.build())
+}
@@ -0,0 +1,201 @@
+use crate::element::{Element, ElementGetExt};
+use crate::errors::Error;
+use super::keyframe::{self, Keyframe};
+
+/// Intrinsic components are listed in a component chain only once they
+/// have been changed, otherwise the chain just flags them as default:
+/// (flag, ID element, display name, match name).
+const INTRINSICS: [(&str, &str, &str, &str); 4] = [
+	("DefaultMotion", "DefaultMotionComponentID", "Motion", "AE.ADBE Motion"),
+	("DefaultOpacity", "DefaultOpacityComponentID", "Opacity", "AE.ADBE Opacity"),
+	("DefaultVol", "DefaultVolumeComponentID", "Volume", "Internal Volume Stereo"),
+	("DefaultVol", "DefaultChannelVolumeComponentID", "Channel Volume", "Internal Channel Volume Stereo"),
+];
+
+/// An effect (component) applied to a clip,
+/// e.g. Motion, Opacity, Volume or an added "Warp Stabilizer".
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Effect {
+	/// ID of the component inside of its chain.
+	pub id: u32,
+	/// Name shown in the Effect Controls panel.
+	pub display_name: String,
+	/// Name identifying the kind of effect, e.g. `AE.ADBE Lumetri`.
+	pub match_name: String,
+	/// Motion, Opacity, Volume etc. which every clip has.
+	pub intrinsic: bool,
+	/// Turned off with the *fx* toggle.
+	pub bypass: bool,
+	pub params: Vec<EffectParam>,
+}
+
+impl Effect {
+	/// `component` is the object referenced from the `<Components>` of a chain,
+	/// `params` are its already resolved `<Param>` objects.
+	pub fn new(component: &Element, chain: &Element, params: Vec<EffectParam>) -> Result<Self, Error> {
+		let inner = component.find("Component").unwrap_or(component);
+		let id: u32 = inner.get("ID")?.text().parse().unwrap_or(0);
+		let intrinsic = Self::intrinsic_names(chain)
+			.into_iter()
+			.find(|(intrinsic_id, _, _)| *intrinsic_id == id);
+
+		let text_of = |elem: &Element, name: &str| elem
+			.get(name)
+			.map(|found| found.text().trim().to_owned())
+			.ok()
+			.filter(|text| !text.is_empty());
+
+		let match_name = component
+			.find("MatchName")
+			.map(|found| found.text().trim().to_owned())
+			.or_else(|| intrinsic.map(|(_, _, match_name)| match_name.to_owned()))
+			.unwrap_or_else(|| component.name().to_owned());
+
+		let display_name = text_of(inner, "DisplayName")
+			.or_else(|| text_of(inner, "InstanceName"))
+			.or_else(|| intrinsic.map(|(_, display_name, _)| display_name.to_owned()))
+			.unwrap_or_else(|| match_name.to_owned());
+
+		Ok(Self {
+			id,
+			display_name,
+			match_name,
+			intrinsic: intrinsic.is_some() || text_of(inner, "Intrinsic").as_deref() == Some("true"),
+			bypass: text_of(inner, "Bypass").as_deref() == Some("true"),
+			params,
+		})
+	}
+
+	/// Intrinsic effects flagged as default in the chain.
+	pub(crate) fn defaults(chain: &Element) -> Vec<Self> {
+		INTRINSICS
+			.iter()
+			.filter(|(flag, _, _, _)|
+				chain.get(flag).map(|elem| elem.text() == "true").unwrap_or(false)
+			)
+			.filter_map(|(_, id_elem, display_name, match_name)| {
+				let id = chain.get(id_elem).ok()?.text().parse().ok()?;
+				Some(Self {
+					id,
+					display_name: display_name.to_string(),
+					match_name: match_name.to_string(),
+					intrinsic: true,
+					bypass: false,
+					params: Vec::new(),
+				})
+			})
+			.collect()
+	}
+
+	/// IDs of the intrinsic components of a chain with their names.
+	fn intrinsic_names(chain: &Element) -> Vec<(u32, &'static str, &'static str)> {
+		INTRINSICS
+			.iter()
+			.filter_map(|(_, id_elem, display_name, match_name)| {
+				let id = chain.get(id_elem).ok()?.text().parse().ok()?;
+				Some((id, *display_name, *match_name))
+			})
+			.collect()
+	}
+
+	pub fn param(&self, name: &str) -> Option<&EffectParam> {
+		self.params.iter().find(|param| param.name == name)
+	}
+}
+
+/// A single parameter of an effect, e.g. Scale of Motion.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EffectParam {
+	pub name: String,
+	/// The value when the parameter isn't animated.
+	pub value: ParamValue,
+	/// Empty when the parameter isn't animated.
+	pub keyframes: Vec<Keyframe>,
+}
+
+impl EffectParam {
+	/// `elem` is a `VideoComponentParam`, `AudioComponentParam` etc.
+	pub fn new(elem: &Element) -> Result<Self, Error> {
+		let name = match elem.get("Name") {
+			Ok(name_elem) => name_elem.text(),
+			// Some parameters (e.g. Motion's Anchor Point) are unnamed
+			Err(_) => String::new()
+		};
+		let value = match elem.get("CurrentValue") {
+			Ok(current_value) => ParamValue::parse(&current_value.text()),
+			// Otherwise the value is the second field of the first keyframe
+			Err(_) => match elem.get("StartKeyframe") {
+				Ok(start_keyframe) => start_keyframe
+					.text()
+					.split(',')
+					.nth(1)
+					.map(ParamValue::parse)
+					.unwrap_or_default(),
+				Err(_) => ParamValue::Empty
+			}
+		};
+		let keyframes = match elem.get("Keyframes") {
+			Ok(keyframes_elem) => keyframe::decode(&keyframes_elem.text()),
+			Err(_) => Vec::new()
+		};
+		Ok(Self {
+			name,
+			value,
+			keyframes,
+		})
+	}
+
+	pub fn is_animated(&self) -> bool {
+		!self.keyframes.is_empty()
+	}
+
+	/// Value at `time` seconds of the clip's source (see `Keyframe::time`).
+	pub fn value_at(&self, time: f64) -> ParamValue {
+		keyframe::evaluate(&self.keyframes, time).unwrap_or_else(|| self.value.clone())
+	}
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ParamValue {
+	#[default]
+	Empty,
+	Bool(bool),
+	Number(f64),
+	/// Positions and anchor points, `x:y` in the project file.
+	Point(f64, f64),
+	Text(String),
+}
+
+impl ParamValue {
+	pub fn parse(text: &str) -> Self {
+		let text = text.trim();
+		if text.is_empty() {
+			return ParamValue::Empty;
+		}
+		match text {
+			"true" => return ParamValue::Bool(true),
+			"false" => return ParamValue::Bool(false),
+			_ => {}
+		}
+		if let Ok(number) = text.parse() {
+			return ParamValue::Number(number);
+		}
+		let mut coords = text.split(':').map(str::parse::<f64>);
+		if let (Some(Ok(x)), Some(Ok(y)), None) = (coords.next(), coords.next(), coords.next()) {
+			return ParamValue::Point(x, y);
+		}
+		ParamValue::Text(text.to_owned())
+	}
+
+	pub fn as_f64(&self) -> Option<f64> {
+		match self {
+			ParamValue::Number(number) => Some(*number),
+			ParamValue::Bool(value) => Some(if *value { 1. } else { 0. }),
+			_ => None
+		}
+	}
+}
@@ -0,0 +1,92 @@
+use super::effect::ParamValue;
+use crate::TICKS_PER_SECOND;
+
+/// How the value changes between a keyframe and the next one.
+/// The `StartKeyframe`s of saved projects carry `0` for linear, the codes
+/// of other interpolations, e.g. Hold or Bezier, are kept as they are
+/// until they're checked against a saved project.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Interpolation {
+	Linear,
+	Other(u32),
+}
+
+impl From<u32> for Interpolation {
+	fn from(code: u32) -> Self {
+		match code {
+			0 => Interpolation::Linear,
+			other => Interpolation::Other(other)
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Keyframe {
+	/// Seconds of the clip: `Cut::in_point` plus the seconds since the cut started.
+	/// The `StartKeyframe` lies far before zero.
+	pub time: f64,
+	pub value: ParamValue,
+	pub interpolation: Interpolation,
+}
+
+impl Keyframe {
+	/// Decodes a single keyframe: `ticks,value,interpolation,...`.
+	/// The remaining fields are ignored.
+	pub fn parse(text: &str) -> Option<Self> {
+		let mut fields = text.trim().split(',');
+		let ticks: i64 = fields.next()?.trim().parse().ok()?;
+		let value = ParamValue::parse(fields.next()?);
+		let interpolation = fields
+			.next()
+			.and_then(|code| code.trim().parse::<u32>().ok())
+			.map(Interpolation::from)
+			.unwrap_or(Interpolation::Linear);
+		Some(Self {
+			time: ticks as f64 / TICKS_PER_SECOND as f64,
+			value,
+			interpolation,
+		})
+	}
+}
+
+/// Decodes the `<Keyframes>` of a `Param`, which are separated by semicolons.
+/// Malformed keyframes are skipped, the result is sorted by time.
+pub fn decode(text: &str) -> Vec<Keyframe> {
+	let mut keyframes: Vec<Keyframe> = text
+		.split(';')
+		.filter(|keyframe| !keyframe.trim().is_empty())
+		.filter_map(Keyframe::parse)
+		.collect();
+	keyframes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+	keyframes
+}
+
+/// Value of an animated parameter at `time` (in the same time as `Keyframe::time`).
+/// Before the first and after the last keyframe the value stays constant.
+/// Keyframes with another interpolation are evaluated linearly as well.
+pub fn evaluate(keyframes: &[Keyframe], time: f64) -> Option<ParamValue> {
+	let first = keyframes.first()?;
+	if time <= first.time {
+		return Some(first.value.clone());
+	}
+	for pair in keyframes.windows(2) {
+		let (from, to) = (&pair[0], &pair[1]);
+		if time >= to.time {
+			continue;
+		}
+		let t = (time - from.time) / (to.time - from.time);
+		let lerp = |a: f64, b: f64| a + (b - a) * t;
+		return Some(match (&from.value, &to.value) {
+			(ParamValue::Number(a), ParamValue::Number(b)) =>
+				ParamValue::Number(lerp(*a, *b)),
+			(ParamValue::Point(ax, ay), ParamValue::Point(bx, by)) =>
+				ParamValue::Point(lerp(*ax, *bx), lerp(*ay, *by)),
+			// Booleans, text etc. can't be blended
+			_ => from.value.clone()
+		});
+	}
+	keyframes.last().map(|last| last.value.clone())
+}
@@ -0,0 +1,67 @@
+use serde_json::Value;
+
+use crate::element::Element;
+use crate::TICKS_PER_SECOND;
+
+/// A marker of a sequence or clip.
+///
+/// Premiere keeps the markers of a `<Markers>` object as `<DVAMarker>`s
+/// holding JSON, e.g. `{"DVAMarker":{"mName":"","mStartTime":{"ticks":"0"},...}}`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Marker {
+	/// GUID which stays the same when the marker is edited, may be empty.
+	pub id: String,
+	pub name: String,
+	pub comment: String,
+	/// Seconds of the sequence for sequence markers, of the source for clip markers.
+	pub start: f64,
+	/// Length in seconds, `0` for markers on a single frame.
+	pub duration: f64,
+	/// Index of the marker color, `0` (green) when none is saved.
+	pub color: u32,
+}
+
+impl Marker {
+	/// Decodes the JSON of a single `<DVAMarker>`.
+	pub fn parse(json: &str) -> Option<Self> {
+		let value: Value = serde_json::from_str(json).ok()?;
+		let marker = value.get("DVAMarker")?;
+		let text = |name: &str| marker
+			.get(name)
+			.and_then(Value::as_str)
+			.unwrap_or_default()
+			.to_owned();
+		// Ticks are saved as strings, they don't fit in a double
+		let seconds = |name: &str| marker
+			.get(name)
+			.and_then(|time| time.get("ticks"))
+			.and_then(|ticks| match ticks {
+				Value::String(ticks) => ticks.parse::<i64>().ok(),
+				ticks => ticks.as_i64(),
+			})
+			.map_or(0., |ticks| ticks as f64 / TICKS_PER_SECOND as f64);
+		Some(Self {
+			id: text("mMarkerID"),
+			name: text("mName"),
+			comment: text("mComment"),
+			start: seconds("mStartTime"),
+			duration: seconds("mDuration"),
+			color: marker
+				.get("mColorIndex")
+				.and_then(Value::as_u64)
+				.unwrap_or(0) as u32,
+		})
+	}
+}
+
+/// Markers of a `<Markers>` object sorted by time, malformed ones are skipped.
+pub(crate) fn decode(markers: &Element) -> Vec<Marker> {
+	let mut decoded: Vec<Marker> = markers
+		.children()
+		.filter(|child| child.name() == "DVAMarker")
+		.filter_map(|child| Marker::parse(&child.text()))
+		.collect();
+	decoded.sort_by(|a, b| a.start.partial_cmp(&b.start).unwrap());
+	decoded
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::time::Duration;
 use super::Size;
@@ -18,27 +18,64 @@ use {
 	}
 };
 
+/// Index of a medium in `PremiereFile::media`, cuts refer to
+/// the medium they play with it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct MediumId(pub usize);
+
+/// Every medium once, in the order clips first play them.
+///
+/// Media are told apart by their file path, or by their `ObjectUID`
+/// when they have no file.
 #[derive(Debug, Default)]
 pub struct PremiereMedia {
-	pub media: HashSet<Box<PremiereMedium>>
+	media: Vec<PremiereMedium>,
+	ids: HashMap<String, MediumId>,
 }
 
 impl PremiereMedia {
-	pub(crate) fn insert(&mut self, medium: PremiereMedium) -> Box<PremiereMedium> {
-		if self.media.contains(&medium) {
-			return match self.media.get(&medium) {
-				Some(value) => value.to_owned(),
-				None => unreachable!()
-			};
+	/// Returns the ID of the medium, adding it unless it's already there.
+	pub(crate) fn insert(&mut self, medium: PremiereMedium) -> MediumId {
+		let key = if medium.file_path.is_empty() { &medium.uid } else { &medium.file_path };
+		if let Some(id) = self.ids.get(key) {
+			return *id;
 		}
-		self.media.insert(Box::new(medium.to_owned()));
-		self.media.get(&medium).unwrap().to_owned()
+		let id = MediumId(self.media.len());
+		self.ids.insert(key.to_owned(), id);
+		self.media.push(medium);
+		id
+	}
+
+	/// The media in the order of their IDs.
+	pub(crate) fn into_vec(self) -> Vec<PremiereMedium> {
+		self.media
+	}
+
+	pub fn get(&self, id: MediumId) -> Option<&PremiereMedium> {
+		self.media.get(id.0)
+	}
+
+	pub fn len(&self) -> usize {
+		self.media.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.media.is_empty()
+	}
+
+	pub fn iter(&self) -> std::slice::Iter<'_, PremiereMedium> {
+		self.media.iter()
 	}
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PremiereMedium {
+	/// `ObjectUID` of the `Media`, kept when the file is relinked.
+	pub(crate) uid: String,
 	file_name: String, // TODO: impl
 	file_path: String, // TODO: impl
 	pub frame_rate: u64,
@@ -63,6 +100,13 @@ impl PremiereMedium {
 	}
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl PremiereMedium {
+	pub fn duration(&self) -> Duration {
+		self.duration
+	}
+}
+
 impl PremiereMedium {
 	pub fn new(
 		file_name: String,
@@ -72,6 +116,7 @@ impl PremiereMedium {
 		size: Size,
 	) -> Self {
 		Self {
+			uid: String::new(),
 			file_name,
 			file_path,
 			frame_rate,
@@ -79,6 +124,18 @@ impl PremiereMedium {
 			size
 		}
 	}
+
+	pub fn uid(&self) -> &str {
+		&self.uid
+	}
+
+	pub fn file_name(&self) -> &str {
+		&self.file_name
+	}
+
+	pub fn file_path(&self) -> &str {
+		&self.file_path
+	}
 }
 
 impl PartialEq for PremiereMedium {
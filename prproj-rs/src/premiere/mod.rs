@@ -1,40 +1,248 @@
+#[cfg(feature = "async")]
+pub mod async_reader;
+pub mod effect;
+pub mod keyframe;
+pub mod marker;
 pub mod media;
+pub mod project_item;
 pub mod reader;
 pub mod sequence;
+pub mod settings;
+pub mod speed;
+pub mod sync;
+pub mod transition;
 
-pub use media::{PremiereMedia, PremiereMedium};
+pub use effect::{Effect, EffectParam, ParamValue};
+pub use keyframe::{Interpolation, Keyframe};
+pub use marker::Marker;
+pub use media::{MediumId, PremiereMedia, PremiereMedium};
+pub use project_item::ProjectItem;
 pub use reader::{PremiereReader, Reader};
 pub use sequence::{PremiereSequence, PremiereSequences};
+pub use settings::{ChannelType, FieldOrder, FourCC, PreviewSettings, SequenceSettings};
+pub use speed::{Speed, TimeInterpolation};
+pub use sync::SyncSlip;
+pub use transition::{Transition, TransitionAlignment};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 use std::borrow::Borrow;
+use std::fmt;
+use crate::timecode::Timecode;
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Clone, Copy, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Size {
 	pub width: u32,
 	pub height: u32,
 }
 
+/// What a `Cut` plays back.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CutSource {
+	/// A media file, see `PremiereFile::medium`.
+	Medium(MediumId),
+	/// Another sequence nested inside the timeline,
+	/// identified by its `PremiereSequence::id`.
+	Sequence(u32),
+}
+
+impl Default for CutSource {
+	fn default() -> Self {
+		CutSource::Medium(MediumId::default())
+	}
+}
+
+/// Kind of track a `Cut` lies on.
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
-#[derive(Default, Debug)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TrackKind {
+	#[default]
+	Video,
+	Audio,
+}
+
+impl TrackKind {
+	/// Name of a track as shown in Premiere, e.g. `V1` or `A2`.
+	pub fn track_name(self, track: usize) -> String {
+		match self {
+			TrackKind::Video => format!("V{}", track + 1),
+			TrackKind::Audio => format!("A{}", track + 1),
+		}
+	}
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cut {
+	/// `ObjectID` of the clip track item.
+	pub id: u32,
+	/// Position on the timeline in seconds.
 	pub start: f64,
 	pub end: f64,
-	//	media: &'a RefCell<PremiereMedia>,
-	medium: Box<PremiereMedium>,
+	/// In and out points of the clip in seconds of its source.
+	/// For nested sequences it's the time inside the nested sequence.
+	/// Only equal to the range used when the clip plays at 100%, see `source_range`.
+	pub in_point: f64,
+	pub out_point: f64,
+	pub kind: TrackKind,
+	/// Index of the track in its track group, `0` is V1 or A1.
+	pub track: usize,
+	source: CutSource,
+	effects: Vec<Effect>,
+	speed: Speed,
+	linked: Vec<u32>,
+	grouped: Vec<u32>,
+	/// Turned off with *Enable*, the clip isn't played back.
+	disabled: bool,
+	markers: Vec<Marker>,
+}
+
+impl Cut {
+	pub fn source(&self) -> &CutSource {
+		&self.source
+	}
+
+	/// Intrinsic (Motion, Opacity, Volume...) and added effects
+	/// in the order they are applied.
+	pub fn effects(&self) -> &[Effect] {
+		&self.effects
+	}
+
+	/// Whether an effect with the given display or match name is applied,
+	/// e.g. `"Warp Stabilizer"` or `"AE.ADBE Lumetri"`.
+	pub fn has_effect(&self, name: &str) -> bool {
+		self.effects
+			.iter()
+			.any(|effect| effect.display_name == name || effect.match_name == name)
+	}
+
+	pub fn speed(&self) -> &Speed {
+		&self.speed
+	}
+
+	/// IDs of the cuts linked to this one, usually the audio
+	/// of a video clip or the other way round.
+	pub fn linked(&self) -> &[u32] {
+		&self.linked
+	}
+
+	pub fn is_linked(&self) -> bool {
+		!self.linked.is_empty()
+	}
+
+	/// IDs of the cuts grouped with this one with *Group*, on any track.
+	/// Unlike `linked` this isn't limited to the audio and video of a clip.
+	pub fn grouped(&self) -> &[u32] {
+		&self.grouped
+	}
+
+	pub fn is_grouped(&self) -> bool {
+		!self.grouped.is_empty()
+	}
+
+	pub fn is_disabled(&self) -> bool {
+		self.disabled
+	}
+
+	/// Markers of the clip, their times are seconds of its source.
+	pub fn markers(&self) -> &[Marker] {
+		&self.markers
+	}
+
+	/// Seconds of the source played back by the cut, taking
+	/// speed changes and time remapping into account.
+	pub fn source_range(&self) -> (f64, f64) {
+		let played = self.speed.source_elapsed(self.in_point, self.end - self.start);
+		(self.in_point, self.in_point + played)
+	}
+
+	/// Time of the source shown at `time` seconds of the timeline.
+	pub fn source_time_at(&self, time: f64) -> f64 {
+		let played = self.speed.source_elapsed(self.in_point, time - self.start);
+		if self.speed.reverse {
+			self.source_range().1 - played
+		} else {
+			self.in_point + played
+		}
+	}
+
+	/// Samples a parameter of an effect, e.g. `("Motion", "Scale")`,
+	/// at `time` seconds of the timeline.
+	pub fn param_value_at(&self, effect: &str, param: &str, time: f64) -> Option<ParamValue> {
+		let param = self.effects
+			.iter()
+			.filter(|found| found.display_name == effect || found.match_name == effect)
+			.find_map(|found| found.param(param))?;
+		Some(param.value_at(self.in_point + (time - self.start)))
+	}
 }
 
 #[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
 impl Cut {
+	/// Index of the medium in the project's `media`.
+	#[wasm_bindgen(getter)]
+	pub fn medium(&self) -> Option<usize> {
+		match self.source {
+			CutSource::Medium(id) => Some(id.0),
+			CutSource::Sequence(_) => None
+		}
+	}
 	#[wasm_bindgen(getter)]
-	pub fn medium(&self) -> PremiereMedium {
-		*self.medium.clone()
+	pub fn sequence(&self) -> Option<u32> {
+		match self.source {
+			CutSource::Medium(_) => None,
+			CutSource::Sequence(id) => Some(id)
+		}
 	}
 }
 
-#[derive(Clone)]
+/// Where a cut lies in a sequence.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CutPosition {
+	/// `Cut::id`, only meaningful within its own version of the project.
+	pub id: u32,
+	/// Name of the track, e.g. `V1`.
+	pub track: String,
+	/// Position on the timeline in seconds.
+	pub start: f64,
+	pub end: f64,
+	pub start_timecode: Timecode,
+	pub end_timecode: Timecode,
+	/// In and out points in seconds of the source.
+	pub in_point: f64,
+	pub out_point: f64,
+}
+
+impl CutPosition {
+	pub(crate) fn new(cut: &Cut, seq: &PremiereSequence) -> Self {
+		Self {
+			id: cut.id,
+			track: cut.kind.track_name(cut.track),
+			start: cut.start,
+			end: cut.end,
+			start_timecode: seq.timecode_at(cut.start),
+			end_timecode: seq.timecode_at(cut.end),
+			in_point: cut.in_point,
+			out_point: cut.out_point,
+		}
+	}
+}
+
+impl fmt::Display for CutPosition {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} {}-{}", self.track, self.start_timecode, self.end_timecode)
+	}
+}
+
+#[derive(Clone, Copy)]
 enum FindWith {
 	ID,
 	UID,
@@ -51,13 +259,32 @@ impl FindWith {
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Cuts {
 	cuts: Vec<Cut>
 }
 
 impl Cuts {
+	/// Returns the index of the pushed cut.
 	fn push(&mut self, cut: Cut) -> usize {
 		self.cuts.push(cut);
+		self.cuts.len() - 1
+	}
+
+	pub fn get(&self, index: usize) -> Option<&Cut> {
+		self.cuts.get(index)
+	}
+
+	pub fn len(&self) -> usize {
 		self.cuts.len()
 	}
+
+	pub fn is_empty(&self) -> bool {
+		self.cuts.is_empty()
+	}
+
+	pub fn iter(&self) -> std::slice::Iter<'_, Cut> {
+		self.cuts.iter()
+	}
 }
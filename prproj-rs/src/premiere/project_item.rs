@@ -0,0 +1,37 @@
+use crate::element::{Element, ElementGetExt};
+
+/// An entry of the Project panel, a bin or a clip or sequence inside of one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProjectItem {
+	/// `ObjectUID` of the project item.
+	pub uid: String,
+	pub name: String,
+	/// Names of the bins the item lies in, outermost first.
+	/// Empty for items in the root bin.
+	pub bin: Vec<String>,
+	pub is_bin: bool,
+}
+
+impl ProjectItem {
+	/// `elem` is a `ClipProjectItem` or `BinProjectItem`.
+	pub(crate) fn new(elem: &Element, bin: &[String]) -> Self {
+		let name = elem
+			.get("ProjectItem")
+			.ok()
+			.and_then(|item| item.get("Name").ok())
+			.map(|name| name.text().trim().to_owned())
+			.unwrap_or_default();
+		Self {
+			uid: elem.attr("ObjectUID").unwrap_or_default().to_owned(),
+			name,
+			bin: bin.to_vec(),
+			is_bin: elem.name() == "BinProjectItem",
+		}
+	}
+
+	/// The bins joined with slashes, e.g. `Footage/Day 1`.
+	pub fn bin_path(&self) -> String {
+		self.bin.join("/")
+	}
+}
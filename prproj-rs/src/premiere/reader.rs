@@ -1,20 +1,23 @@
-use std::cell::{RefCell, RefMut};
 use crate::element::{Element, ElementGetExt};
 
-/// https://docs.rs/libflate/0.1.16/libflate/gzip/index.html
-use libflate::gzip;
-use std::io::Read;
+use crate::decompress::{self, Decompress, Libflate};
+use std::io::{BufReader, Cursor, Read};
 
 use std::collections::HashMap;
-use crate::errors::{Error, NotFoundError, NotFoundErrorData, MultipleNotFoundErrorData};
+use crate::errors::{Error, InvalidValueData, NotFoundError, NotFoundErrorData, MultipleNotFoundErrorData};
 use itertools::Itertools;
 
-use super::{PremiereMedia, PremiereSequence, PremiereSequences, Size, Cut, FindWith};
+use super::{MediumId, PremiereMedia, PremiereSequence, PremiereSequences, SequenceSettings, Size, Cut, CutSource, TrackKind, Transition, FindWith};
+use super::effect::{Effect, EffectParam};
+use super::marker::{self, Marker};
+use super::speed::Speed;
 use crate::{sorted_vec, TICKS_PER_SECOND};
-use crate::premiere::PremiereMedium;
+use crate::premiere::{PremiereMedium, ProjectItem};
 use std::time::Duration;
 use std::path::Path;
 use std::fs::File;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 /// Reads data and produces sequences, media.
 /// ```
@@ -39,11 +42,56 @@ use std::fs::File;
 /// ```
 #[derive(Debug)]
 pub struct PremiereReader {
-	media: RefCell<PremiereMedia>,
+	media: PremiereMedia,
 	sequences: PremiereSequences,
+	items: Vec<ProjectItem>,
 	root: Element,
+	index: ObjectIndex,
+	flatten_nested: bool,
+	compressed: bool,
+	#[cfg(feature = "rayon")]
+	parallel: bool,
 }
 
+/// Positions among the nodes of the root of the objects with
+/// an `ObjectID` or `ObjectUID`, so they are found without a scan.
+#[derive(Debug, Default)]
+struct ObjectIndex {
+	ids: HashMap<String, usize>,
+	uids: HashMap<String, usize>,
+}
+
+impl ObjectIndex {
+	fn new(root: &Element) -> Self {
+		let mut index = Self::default();
+		for (position, node) in root.nodes().enumerate() {
+			let elem = match node.as_element() {
+				Some(elem) => elem,
+				None => continue
+			};
+			// The first object with an ID wins, as it did when scanning
+			if let Some(id) = elem.attr(FindWith::ID.value()) {
+				index.ids.entry(id.to_owned()).or_insert(position);
+			}
+			if let Some(uid) = elem.attr(FindWith::UID.value()) {
+				index.uids.entry(uid.to_owned()).or_insert(position);
+			}
+		}
+		index
+	}
+
+	fn position(&self, identifier: &str, find_with: FindWith) -> Option<usize> {
+		let positions = match find_with {
+			FindWith::ID => &self.ids,
+			FindWith::UID => &self.uids
+		};
+		positions.get(identifier).copied()
+	}
+}
+
+/// IDs of the cuts of each `Link` or group of a sequence.
+type CutGroups = Vec<Vec<u32>>;
+
 /// Used to map the Sequence ID to a Vector of TrackGroup
 /// ObjectRef.
 type HashMapWithVector = HashMap<usize, Vec<String>>;
@@ -62,9 +110,48 @@ impl HashMapLengthWithVector for HashMapWithVector {
 	}
 }
 
+/// Everything read from a project.
+///
+/// Plain owned data, `Send` and `Sync` unlike the `PremiereReader` which
+/// keeps the parsed XML, so it can be returned from other threads.
+/// Cuts refer to the media they play by their `MediumId`.
+///
+/// With the `serde` feature the whole model can be serialized and
+/// deserialized, e.g. to cache parsed projects. Fields keep their Rust
+/// names, enum variants are `snake_case` and times are seconds unless
+/// documented otherwise (`frame_rate`s are ticks per frame, durations are
+/// `{"secs", "nanos"}`). A `Cut`'s source is either `{"medium": <index>}`
+/// into `media` or `{"sequence": <id>}`. `cuts` and `timeline` are plain arrays, the
+/// `cut` of a timeline item as well as `from_cut`/`to_cut` of a transition
+/// index into the sequence's `cuts`.
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// # use std::path::PathBuf;
+/// # use prproj::{PremiereReader, PremiereFile, Reader};
+/// # let mut path = std::env::current_dir().unwrap();
+/// # path.pop();
+/// # path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+/// let mut reader = PremiereReader::from_path(&path);
+/// reader.read().unwrap();
+/// let json = serde_json::to_string(&reader.take()).unwrap();
+/// let project: PremiereFile = serde_json::from_str(&json).unwrap();
+/// assert_eq!(project.sequences.len(), 2);
+/// # }
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PremiereFile {
 	pub media: Vec<PremiereMedium>,
-	pub sequences: Vec<PremiereSequence>
+	pub sequences: Vec<PremiereSequence>,
+	/// Bins and items of the Project panel.
+	pub items: Vec<ProjectItem>,
+}
+
+impl PremiereFile {
+	/// The medium a `CutSource::Medium` refers to.
+	pub fn medium(&self, id: MediumId) -> Option<&PremiereMedium> {
+		self.media.get(id.0)
+	}
 }
 
 // Generic over error
@@ -76,74 +163,142 @@ pub trait Reader<T> {
 
 impl Reader<Error> for PremiereReader {
 	fn new(xml: &[u8]) -> Self {
-		let to_str = |bytes: &[u8]|
-			String::from_utf8(bytes.to_vec()).unwrap();
-
-		if xml.iter().take(2).eq([0x1f, 0x8b].iter()) {
-			let mut buf = Vec::new();
-			// https://docs.rs/libflate/0.1.27/libflate/gzip/struct.Decoder.html
-			let mut decoder = gzip::Decoder::new(xml).unwrap();
-			decoder.read_to_end(&mut buf).unwrap();
-			Self {
-				root: to_str(&buf).parse().expect("XML parsing unzipped error"),
-				sequences: Vec::default(),
-				media: RefCell::new(PremiereMedia::default()),
-			}
-		} else {
-			Self {
-				root: to_str(xml).parse().expect("XML parsing raw XML errror"),
-				sequences: Vec::default(),
-				media: RefCell::new(PremiereMedia::default()),
-			}
-		}
+		Self::with_decompressor(xml, &Libflate).expect("XML parsing error")
 	}
 
 	fn read(&mut self) -> Result<(), Error> {
-		let references: HashMapWithVector = Self::get_sequences(&self.root, &mut self.sequences)?;
+		let defaults = self.parse_project_settings();
+		let references: HashMapWithVector = Self::get_sequences(&self.root, &mut self.sequences, &defaults)?;
+		self.parse_sequence_markers();
 		// Non-lexical lifetime in Vec!
 		// https://rust-lang.github.io/rfcs/2094-nll.html#problem-case-3-conditional-control-flow-across-functions
 		// https://github.com/rust-lang/rust/issues/21906#issuecomment-73296543
 		// https://stackoverflow.com/questions/58295535/cannot-borrow-self-as-mutable-more-than-once-at-a-time-when-returning-a-resul
 		// https://stackoverflow.com/questions/38023871/returning-a-reference-from-a-hashmap-or-vec-causes-a-borrow-to-last-beyond-the-s
-		self.resolve_groups(&references)
+		self.resolve_groups(&references)?;
+		let links = self.cut_groups(|seq| &seq.links);
+		let groups = self.cut_groups(|seq| &seq.groups);
+		self.resolve_sequences(links.into_iter().zip(groups).collect());
+		self.items = self.parse_project_items();
+		Ok(())
 	}
 	fn take(mut self) -> PremiereFile {
 		PremiereFile {
-			media: self.media.into_inner().media.into_iter().map(|m| *m).collect(),
-			sequences: self.sequences.into_iter().map(|s| s.into_inner()).collect()
+			media: self.media.into_vec(),
+			sequences: self.sequences,
+			items: self.items,
 		}
 	}
 }
 
 impl PremiereReader {
-	pub fn media(&self) -> &RefCell<PremiereMedia> {
+	pub fn media(&self) -> &PremiereMedia {
 		&self.media
 	}
 
-	pub fn sequences(&self) -> &Vec<RefCell<PremiereSequence>> {
+	pub fn sequences(&self) -> &[PremiereSequence] {
 		&self.sequences
 	}
+
+	/// The parsed XML of the project.
+	pub(crate) fn root(&self) -> &Element {
+		&self.root
+	}
+
+	/// Bins and items of the Project panel, each bin before its contents.
+	pub fn project_items(&self) -> &[ProjectItem] {
+		&self.items
+	}
+
+	/// When set, `read` expands cuts of nested sequences into the media
+	/// they consist of, placed at their position on the outer timeline.
+	/// Otherwise they are kept as cuts with a `CutSource::Sequence`.
+	pub fn set_flatten_nested(&mut self, flatten: bool) {
+		self.flatten_nested = flatten;
+	}
+
+	/// Whether `read` resolves the sequences on rayon's thread pool,
+	/// which it does by default. Turned off it works like without the
+	/// `rayon` feature.
+	#[cfg(feature = "rayon")]
+	pub fn set_parallel(&mut self, parallel: bool) {
+		self.parallel = parallel;
+	}
+	/// Whether the project was gzipped, as Premiere saves it.
+	pub fn is_compressed(&self) -> bool {
+		self.compressed
+	}
+
+	/// Parses a project, gzipped projects are decompressed with `decompressor`.
+	pub fn with_decompressor(bytes: &[u8], decompressor: &dyn Decompress) -> Result<Self, Error> {
+		Self::from_reader_with_decompressor(bytes, decompressor)
+	}
+
+	/// Parses a project straight from a file, stdin, an archive or a network
+	/// stream, decompressing it on the fly when it's gzipped.
+	/// Only the input is streamed: the compressed bytes and the decompressed
+	/// XML text are never held in memory as a whole, but the parsed document
+	/// tree is, so memory still grows with the size of the project.
+	pub fn from_reader<'a, R: Read + 'a>(source: R) -> Result<Self, Error> {
+		Self::from_reader_with_decompressor(source, &Libflate)
+	}
+
+	pub fn from_reader_with_decompressor<'a, R: Read + 'a>(
+		mut source: R,
+		decompressor: &dyn Decompress
+	) -> Result<Self, Error> {
+		// Peek at the magic bytes, putting them back in front
+		let mut magic = [0; 2];
+		let mut peeked = 0;
+		while peeked < magic.len() {
+			match source.read(&mut magic[peeked..])? {
+				0 => break,
+				read => peeked += read,
+			}
+		}
+		let source = Cursor::new(magic).take(peeked as u64).chain(source);
+		let compressed = decompress::is_gzip(&magic[..peeked]);
+		let xml: Box<dyn Read + 'a> = if compressed {
+			decompressor.decompress(Box::new(source))?
+		} else {
+			Box::new(source)
+		};
+		let root = Element::from_reader(&mut quick_xml::Reader::from_reader(BufReader::new(xml)))?;
+		Ok(Self::with_root(root, compressed))
+	}
+
+	pub(crate) fn with_root(root: Element, compressed: bool) -> Self {
+		Self {
+			index: ObjectIndex::new(&root),
+			root,
+			sequences: Vec::default(),
+			items: Vec::default(),
+			media: PremiereMedia::default(),
+			flatten_nested: false,
+			compressed,
+			#[cfg(feature = "rayon")]
+			parallel: true,
+		}
+	}
+
 	pub fn from_path(path: &Path) -> Self {
-		let mut buffer: Vec<u8> = Vec::new();
-		let mut file = File::open(Path::new(path)).unwrap();
-		file.read_to_end(&mut buffer).unwrap();
-		PremiereReader::new(&buffer)
+		let file = File::open(path).expect("Opening project error");
+		PremiereReader::from_reader(file).expect("XML parsing error")
 	}
 
 	/// Searches for sequences which are direct children of the `self.root`.
 	/// Once it finds one, pushes a `PremiereSequence` to `sequences`.
 	/// Returns `HashMapWithVector`.
-	fn get_sequences(root: &Element, sequences: &mut PremiereSequences)
+	fn get_sequences(root: &Element, sequences: &mut PremiereSequences, defaults: &SequenceSettings)
 	                 -> Result<HashMapWithVector, Error>
 	{
 		let mut references: HashMapWithVector = HashMap::new();
 		for child in root.children() {
 			if child.name() == "Sequence" {
-				println!("<Sequence ObjectUID={:?}>", child.attr("ObjectUID").unwrap());
-				let seq = PremiereSequence::new(child)?;
+				let seq = PremiereSequence::with_settings(child, defaults.to_owned())?;
 				let seq_index = sequences.len();
 				references.insert(seq_index, seq.track_groups.to_owned());
-				sequences.push(RefCell::new(seq));
+				sequences.push(seq);
 			}
 		}
 		Ok(references)
@@ -153,7 +308,8 @@ impl PremiereReader {
 	fn parse_video_track_group(
 		&self,
 		vtg: &minidom::element::Element,
-		mut seq: RefMut<PremiereSequence>
+		seq: &mut PremiereSequence,
+		media: &mut PremiereMedia
 	) -> Result<(), Error> {
 		let (frame_rect_elem, track_group_elem) =
 			Self::get_elems_with_names(
@@ -170,6 +326,73 @@ impl PremiereReader {
 
 		seq.size.width = frame_rect.next().unwrap().parse().unwrap();
 		seq.size.height = frame_rect.next().unwrap().parse().unwrap();
+		seq.settings.frame_size = seq.size;
+		seq.settings.frame_rate = track_group_elem.get("FrameRate")?.text().parse().unwrap_or(0);
+		seq.settings.read_pixel_aspect_ratio(vtg);
+		if let Ok(field_type) = vtg.get("FieldType") {
+			seq.settings.field_order = field_type.text().parse::<u32>().unwrap_or(0).into();
+		}
+		self.parse_track_group(track_group_elem, TrackKind::Video, seq, media)
+	}
+
+	fn parse_audio_track_group(
+		&self,
+		atg: &minidom::element::Element,
+		seq: &mut PremiereSequence,
+		media: &mut PremiereMedia
+	) -> Result<(), Error> {
+		let track_group_elem = atg.get("TrackGroup")?;
+		if let Ok(frame_rate) = track_group_elem.get("FrameRate") {
+			seq.settings.read_audio_frame_rate(frame_rate);
+		}
+		// The channel layout is the one of the master track
+		let master_track = atg.get("MasterTrack")
+			.ok()
+			.and_then(|master_track| master_track.attr("ObjectRef"))
+			.and_then(|id| self.try_get_elem_with_id(id, FindWith::ID));
+		if let Some(channel_type) = master_track.and_then(|master_track| master_track.find("ChannelType")) {
+			seq.settings.read_channel_type(channel_type);
+		}
+		self.parse_track_group(track_group_elem, TrackKind::Audio, seq, media)
+	}
+
+	/// Default settings for sequences from the project's `<ProjectSettings>`.
+	fn parse_project_settings(&self) -> SequenceSettings {
+		let mut settings = SequenceSettings::default();
+		let project_settings = match self.root.children().find(|child| child.name() == "ProjectSettings") {
+			Some(project_settings) => project_settings,
+			None => return settings
+		};
+		let referenced = |name: &str| project_settings
+			.get(name)
+			.ok()
+			.and_then(|reference| reference.attr("ObjectRef"))
+			.and_then(|id| self.try_get_elem_with_id(id, FindWith::ID));
+
+		if let Some(video_settings) = referenced("VideoSettings") {
+			settings.read_video_settings(video_settings);
+		}
+		if let Some(audio_settings) = referenced("AudioSettings") {
+			settings.read_audio_settings(audio_settings);
+		}
+		if let Some(compile_settings) = referenced("VideoCompileSettings") {
+			settings.read_video_compile_settings(compile_settings);
+		}
+		if let Some(compile_settings) = referenced("AudioCompileSettings") {
+			settings.read_audio_compile_settings(compile_settings);
+		}
+		settings
+	}
+
+	/// Goes through the tracks of a `<TrackGroup>` pushing a `Cut`
+	/// for every clip on them.
+	fn parse_track_group(
+		&self,
+		track_group_elem: &Element,
+		kind: TrackKind,
+		seq: &mut PremiereSequence,
+		media: &mut PremiereMedia
+	) -> Result<(), Error> {
 		let mut track_refs: Vec<&str> = Vec::new();
 		let mut track_refs_found_with: &FindWith = &FindWith::ID;
 
@@ -183,193 +406,501 @@ impl PremiereReader {
 				track_refs_found_with = &FindWith::ID;
 			}
 		}
-		for track_ref in track_refs {
-			let track_items: &Element
+		for (track_index, track_ref) in track_refs.into_iter().enumerate() {
+			let clip_track: &Element
 				// VideoClipTrack or AudioClipTrack so can't set name
-				= self.get_elem_with_id(track_ref, track_refs_found_with.clone())?
-				.get("ClipTrack")?
-				.get("ClipItems")?
-				.get("TrackItems")?;
-
-			let mut track_item_refs: Vec<&str> = Vec::new();
-			for track_item in track_items.children() {
-				track_item_refs.push(track_item.get_attr("ObjectRef")?);
+				= self.get_elem_with_id(track_ref, *track_refs_found_with)?
+				.get("ClipTrack")?;
+
+			if clip_track.find("IsMuted").is_some_and(|muted| muted.text() == "true") {
+				seq.muted_tracks.push((kind, track_index));
+			}
+			self.parse_clip_items(clip_track.get("ClipItems")?, kind, track_index, seq, media)?;
+
+			if let Ok(transition_items) = clip_track.get("TransitionItems") {
+				self.parse_transition_items(transition_items, kind, track_index, seq)?;
 			}
+		}
+		Ok(())
+	}
 
-			for clip_track_item in self.get_elems_with_ids(
-				&track_item_refs,
+	/// Pushes a `Cut` for every clip of a track's `<ClipItems>`.
+	fn parse_clip_items(
+		&self,
+		clip_items: &Element,
+		kind: TrackKind,
+		track_index: usize,
+		seq: &mut PremiereSequence,
+		media: &mut PremiereMedia
+	) -> Result<(), Error> {
+		// Empty tracks have no <TrackItems>
+		let track_items = match clip_items.get("TrackItems") {
+			Ok(track_items) => track_items,
+			Err(_) => return Ok(())
+		};
+
+		let mut track_item_refs: Vec<&str> = Vec::new();
+		for track_item in track_items.children() {
+			track_item_refs.push(track_item.get_attr("ObjectRef")?);
+		}
+
+		for clip_track_item in self.get_elems_with_ids(
+			&track_item_refs,
+			FindWith::ID,
+		) {
+			let sub_clip_track_item
+				= clip_track_item.get("ClipTrackItem")?;
+
+			let (component_owner_elem, sub_clip_elem, track_item_elem) = Self::get_elems_with_names(
+				sub_clip_track_item,
+				&sorted_vec!["ComponentOwner", "SubClip", "TrackItem"],
+			).into_iter().tuples().next().unwrap();
+
+			let (end_elem, start_elem) = Self::get_elems_with_names(
+				track_item_elem,
+				&sorted_vec!["End", "Start"],
+			).into_iter().tuples().next().unwrap();
+
+			let sub_clip = self.get_elem_with_id(
+				sub_clip_elem.get_attr("ObjectRef")?,
 				FindWith::ID,
-			) {
-				let sub_clip_track_item
-					= clip_track_item.get("ClipTrackItem")?;
+			)?;
 
-				let (sub_clip_elem, track_item_elem) = Self::get_elems_with_names(
-					sub_clip_track_item,
-					&sorted_vec!["SubClip", "TrackItem"],
-				).into_iter().tuples().next().unwrap();
+			let in_point: u64 = start_elem.text().parse().unwrap();
+			let out_point: u64 = end_elem.text().parse().unwrap();
 
-				let (end_elem, start_elem) = Self::get_elems_with_names(
-					track_item_elem,
-					&sorted_vec!["End", "Start"],
+			let (clip_elem, _master_clip_elem, _name_elem) =
+				Self::get_elems_with_names(
+					sub_clip,
+					&sorted_vec!["Clip", "MasterClip", "Name"],
 				).into_iter().tuples().next().unwrap();
 
-				let sub_clip = self.get_elem_with_id(
-					sub_clip_elem.get_attr("ObjectRef")?,
-					FindWith::ID,
-				)?;
+			let clip
+				= self.get_elem_with_id(
+				clip_elem.get_attr("ObjectRef")?,
+				FindWith::ID,
+			)?.get("Clip")?;
 
-				let media_name: String;
-				let media_path: String;
-				let in_point: u64 = start_elem.text().parse().unwrap();
-				let out_point: u64 = end_elem.text().parse().unwrap();
-				let frame_rate: u64;
-				let duration: u64;
-
-				let (clip_elem, _master_clip_elem, _name_elem) =
-					Self::get_elems_with_names(
-						sub_clip,
-						&sorted_vec!["Clip", "MasterClip", "Name"],
-					).into_iter().tuples().next().unwrap();
-
-				let clip
-					= self.get_elem_with_id(
-					clip_elem.get_attr("ObjectRef")?,
-					FindWith::ID,
-				)?.get("Clip")?;
+			let (clip_in_point_elem, clip_out_point_elem, source_elem)
+				= Self::get_elems_with_names(
+				clip,
+				&sorted_vec!["InPoint", "OutPoint", "Source"],
+			).into_iter().tuples().next().unwrap();
 
-				let source_elem
-					= clip.get("Source")?;
+			let source = self.get_elem_with_id(
+				source_elem.get_attr("ObjectRef")?,
+				FindWith::ID,
+			)?;
 
+			// Nested sequences have a SequenceSource instead of a MediaSource
+			let cut_source = if let Ok(sequence_source) = source.get("SequenceSource") {
+				CutSource::Sequence(
+					self.get_sequence_id(
+						sequence_source.get("Sequence")?.get_attr("ObjectURef")?
+					)?
+				)
+			} else {
+				CutSource::Medium(self.parse_media_source(source, media)?)
+			};
 
-				let media_uref =
+			let effects = match component_owner_elem.get("Components") {
+				Ok(components_elem) => self.parse_component_chain(
 					self.get_elem_with_id(
-						source_elem.get_attr("ObjectRef")?,
+						components_elem.get_attr("ObjectRef")?,
 						FindWith::ID,
 					)?
-						.get("MediaSource")?
-						.get("Media")?
-						.get_attr("ObjectURef")?;
-
-				let media = self.get_elem_with_id(media_uref, FindWith::UID)?;
-				let (file_path_elem, title_elem, video_stream_elem): (&Element, &Element, &Element)
-					= Self::get_elems_with_names(
-					media,
-					&sorted_vec!["FilePath", "Title", "VideoStream"])
-					.into_iter().tuples().next().unwrap();
-
-				media_path = {
-					let file_path_elem_text = file_path_elem.text();
-					file_path_elem_text.trim().to_owned()
-				};
+				)?,
+				Err(_) => Vec::new()
+			};
+
+			let speed = Speed::new(clip, clip_track_item, &effects);
+
+			let start = in_point as f64 / TICKS_PER_SECOND as f64;
+			let end = out_point as f64 / TICKS_PER_SECOND as f64;
+			let clip_in_point = clip_in_point_elem.text().parse::<i64>().unwrap_or(0) as f64 / TICKS_PER_SECOND as f64;
+			let clip_out_point = clip_out_point_elem.text().parse::<i64>().unwrap_or(0) as f64 / TICKS_PER_SECOND as f64;
+
+			let cut = Cut {
+				id: clip_track_item.get_attr("ObjectID")?.parse().unwrap_or_default(),
+				start,
+				end,
+				in_point: clip_in_point,
+				out_point: clip_out_point,
+				kind,
+				track: track_index,
+				source: cut_source,
+				effects,
+				speed,
+				linked: Vec::new(),
+				grouped: Vec::new(),
+				disabled: sub_clip_track_item.find("Disabled").is_some_and(|disabled| disabled.text() == "true"),
+				markers: self.parse_markers(clip),
+			};
+
+			seq.cuts.push(cut);
+		}
+		Ok(())
+	}
 
-				media_name = {
-					let title_elem_text = title_elem.text();
-					title_elem_text.trim().to_owned()
-				};
+	/// Pushes a `Transition` for every item of a track's `<TransitionItems>`,
+	/// they're linked to the cuts when the sequence is resolved.
+	fn parse_transition_items(
+		&self,
+		transition_items: &Element,
+		kind: TrackKind,
+		track_index: usize,
+		seq: &mut PremiereSequence
+	) -> Result<(), Error> {
+		let track_items = match transition_items.get("TrackItems") {
+			Ok(track_items) => track_items,
+			Err(_) => return Ok(())
+		};
+
+		let mut transition_refs: Vec<&str> = Vec::new();
+		for track_item in track_items.children() {
+			transition_refs.push(track_item.get_attr("ObjectRef")?);
+		}
+
+		for transition_track_item in self.get_elems_with_ids(
+			&transition_refs,
+			FindWith::ID,
+		) {
+			let transition_elem = transition_track_item.get("TransitionTrackItem")?;
+			let (end_elem, start_elem) = Self::get_elems_with_names(
+				transition_elem.get("TrackItem")?,
+				&sorted_vec!["End", "Start"],
+			).into_iter().tuples().next().unwrap();
 
+			// The transition itself (e.g. a VideoTransitionFilter) is the only referenced object
+			let component = match transition_elem
+				.children()
+				.find_map(|child| child.attr("ObjectRef")) {
+				Some(component_ref) => Some(self.get_elem_with_id(component_ref, FindWith::ID)?),
+				None => None
+			};
+
+			let start = start_elem.text().parse::<i64>().unwrap_or(0) as f64 / TICKS_PER_SECOND as f64;
+			let end = end_elem.text().parse::<i64>().unwrap_or(0) as f64 / TICKS_PER_SECOND as f64;
+			seq.transitions.push(Transition::new(kind, track_index, start, end, component));
+		}
+		Ok(())
+	}
 
-				let video_stream
-					= self.get_elem_with_id(
-					video_stream_elem.get_attr("ObjectRef")?,
+	/// Reads the effects of a `VideoComponentChain` or `AudioComponentChain`.
+	/// Intrinsic effects which were never changed aren't listed in the chain,
+	/// only flagged with e.g. `<DefaultMotion>`, so they are added with no parameters.
+	fn parse_component_chain(&self, chain: &Element) -> Result<Vec<Effect>, Error> {
+		let mut effects: Vec<Effect> = Vec::new();
+		if let Ok(components) = chain.get("ComponentChain")?.get("Components") {
+			for component_ref in components.children() {
+				let component = self.get_elem_with_id(
+					component_ref.get_attr("ObjectRef")?,
 					FindWith::ID,
 				)?;
+				let mut params: Vec<EffectParam> = Vec::new();
+				if let Some(params_elem) = component.find("Params") {
+					for param_ref in params_elem.children() {
+						params.push(EffectParam::new(
+							self.get_elem_with_id(
+								param_ref.get_attr("ObjectRef")?,
+								FindWith::ID,
+							)?
+						)?);
+					}
+				}
+				effects.push(Effect::new(component, chain, params)?);
+			}
+		}
 
-				{
-					let (duration_elem, frame_rate_elem): (&Element, &Element)
-						= Self::get_elems_with_names(
-						video_stream,
-						&sorted_vec!["Duration", "FrameRate"],
-					).into_iter().tuples().next().unwrap();
+		for intrinsic in Effect::defaults(chain) {
+			if !effects.iter().any(|effect| effect.id == intrinsic.id) {
+				effects.push(intrinsic);
+			}
+		}
+		Ok(effects)
+	}
 
-					frame_rate = frame_rate_elem.text().parse().unwrap_or(0);
-					duration = duration_elem.text().parse().unwrap_or(0);
-				};
+	/// Markers of a `<Clip>`, kept in the `Markers` object of its `MarkerOwner`.
+	fn parse_markers(&self, clip: &Element) -> Vec<Marker> {
+		clip.get("MarkerOwner")
+			.ok()
+			.and_then(|owner| owner.get("Markers").ok())
+			.and_then(|markers| markers.attr("ObjectRef"))
+			.and_then(|id| self.try_get_elem_with_id(id, FindWith::ID))
+			.map_or_else(Vec::new, marker::decode)
+	}
 
-				let medium = PremiereMedium::new(
-					media_name.to_owned(),
-					media_path.to_owned(),
-					frame_rate,
-					Duration::from_secs_f64(
-						duration as f64 / TICKS_PER_SECOND as f64
-					),
-					Size {
-						width: 1920,
-						height: 1080,
-					}
-				);
-				let mut media = self.media.borrow_mut();
-
-				let medium_ref
-					= media.insert(medium);
-				let start = in_point as f64 / TICKS_PER_SECOND as f64;
-				let end = out_point as f64 / TICKS_PER_SECOND as f64;
-
-				let cut = Cut {
-					start,
-					end,
-					medium: medium_ref,
-				};
+	/// Markers of the sequences, which Premiere keeps on the clips
+	/// with a `SequenceSource`, e.g. the one of a sequence's master clip.
+	fn parse_sequence_markers(&mut self) {
+		let mut found: Vec<(String, Vec<Marker>)> = Vec::new();
+		for clip in self.root.children().filter_map(|child| child.get("Clip").ok()) {
+			let markers = self.parse_markers(clip);
+			let sequence_uref = clip.get("Source")
+				.ok()
+				.and_then(|source| source.attr("ObjectRef"))
+				.and_then(|id| self.try_get_elem_with_id(id, FindWith::ID))
+				.and_then(|source| source.get("SequenceSource").ok())
+				.and_then(|source| source.get("Sequence").ok())
+				.and_then(|sequence| sequence.attr("ObjectURef"));
+			if let Some(sequence_uref) = sequence_uref.filter(|_| !markers.is_empty()) {
+				found.push((sequence_uref.to_owned(), markers));
+			}
+		}
+		for (sequence_uref, markers) in found {
+			let seq = self.sequences.iter_mut().find(|seq| seq.uid == sequence_uref);
+			// Every clip of a sequence shares its markers, the first one is taken
+			if let Some(seq) = seq.filter(|seq| seq.markers.is_empty()) {
+				seq.markers = markers;
+			}
+		}
+	}
 
-				let seq_index = seq.cuts.push(cut);
-				seq.timeline.add(seq_index, start, end);
+	/// Reads the `Media` which a `MediaSource` points to, adds it
+	/// to `media` and returns the ID of the deduplicated `PremiereMedium`.
+	fn parse_media_source(&self, source: &Element, media: &mut PremiereMedia) -> Result<MediumId, Error> {
+		let media_uref = source
+			.get("MediaSource")?
+			.get("Media")?
+			.get_attr("ObjectURef")?;
+
+		let media_elem = self.get_elem_with_id(media_uref, FindWith::UID)?;
+		let (file_path_elem, title_elem): (&Element, &Element)
+			= Self::get_elems_with_names(
+			media_elem,
+			&sorted_vec!["FilePath", "Title"])
+			.into_iter().tuples().next().unwrap();
+
+		let media_path = {
+			let file_path_elem_text = file_path_elem.text();
+			file_path_elem_text.trim().to_owned()
+		};
+
+		let media_name = {
+			let title_elem_text = title_elem.text();
+			title_elem_text.trim().to_owned()
+		};
+
+		// Audio-only media have no <VideoStream>, the frame rate is then unknown.
+		let (stream_elem, is_video) = match media_elem.get("VideoStream") {
+			Ok(video_stream_elem) => (video_stream_elem, true),
+			Err(_) => (media_elem.get("AudioStream")?, false)
+		};
+
+		let stream
+			= self.get_elem_with_id(
+			stream_elem.get_attr("ObjectRef")?,
+			FindWith::ID,
+		)?;
+
+		let (duration_elem, frame_rate_elem): (&Element, &Element)
+			= Self::get_elems_with_names(
+			stream,
+			&sorted_vec!["Duration", "FrameRate"],
+		).into_iter().tuples().next().unwrap();
+
+		let frame_rate: u64 = if is_video {
+			frame_rate_elem.text().parse().unwrap_or(0)
+		} else {
+			0
+		};
+		let duration: u64 = duration_elem.text().parse().unwrap_or(0);
+
+		let mut medium = PremiereMedium::new(
+			media_name,
+			media_path,
+			frame_rate,
+			Duration::from_secs_f64(
+				duration as f64 / TICKS_PER_SECOND as f64
+			),
+			Size {
+				width: 1920,
+				height: 1080,
 			}
+		);
+		medium.uid = media_uref.to_owned();
+		Ok(media.insert(medium))
+	}
+
+	/// Walks the bins starting with the `RootProjectItem`.
+	fn parse_project_items(&self) -> Vec<ProjectItem> {
+		let mut items = Vec::new();
+		let root_item = self.root
+			.children()
+			.find(|child| child.name() == "RootProjectItem" && child.attr("ObjectUID").is_some());
+		if let Some(root_item) = root_item {
+			self.parse_bin(root_item, &mut Vec::new(), &mut items);
 		}
-		Ok(())
+		items
+	}
+
+	/// Adds the contents of the bin `elem` to `items`,
+	/// `bin` holds the names of the bins on the way to it.
+	fn parse_bin(&self, elem: &Element, bin: &mut Vec<String>, items: &mut Vec<ProjectItem>) {
+		let children = match elem.get("ProjectItemContainer").ok().and_then(|container| container.get("Items").ok()) {
+			Some(children) => children,
+			None => return
+		};
+		for child in children.children() {
+			let item_elem = match child
+				.attr("ObjectURef")
+				.and_then(|uref| self.try_get_elem_with_id(uref, FindWith::UID)) {
+				Some(item_elem) => item_elem,
+				None => continue
+			};
+			let mut item = ProjectItem::new(item_elem, bin);
+			// Renaming a clip only updates the name of its master clip
+			let master_clip_name = item_elem
+				.get("MasterClip")
+				.ok()
+				.and_then(|master_clip| master_clip.attr("ObjectURef"))
+				.and_then(|uref| self.try_get_elem_with_id(uref, FindWith::UID))
+				.and_then(|master_clip| master_clip.get("Name").ok());
+			if let Some(name) = master_clip_name {
+				item.name = name.text().trim().to_owned();
+			}
+			// A bin can't contain itself, guards against broken projects
+			let nested = item.is_bin && !items.iter().any(|found: &ProjectItem| found.uid == item.uid);
+			let name = item.name.to_owned();
+			items.push(item);
+			if nested {
+				bin.push(name);
+				self.parse_bin(item_elem, bin, items);
+				bin.pop();
+			}
+		}
+	}
+
+	/// Finds the `<ID>` of the sequence with the given `ObjectUID`.
+	fn get_sequence_id(&self, sequence_uref: &str) -> Result<u32, Error> {
+		let sequence = self.get_elem_with_id(sequence_uref, FindWith::UID)?;
+		let id = sequence.get("ID")?.text();
+		id.parse().map_err(|_| Error::Invalid(InvalidValueData::new(String::from("ID"), id)))
+	}
+
+	/// IDs of the cuts tied together by each of the objects `refs` picks
+	/// from every sequence, its `Link`s or its groups.
+	/// References to missing objects are ignored.
+	fn cut_groups(&self, refs: impl Fn(&PremiereSequence) -> &[String]) -> Vec<CutGroups> {
+		self.sequences
+			.iter()
+			.map(|seq| refs(seq)
+				.iter()
+				.filter_map(|group| self.try_get_elem_with_id(group, FindWith::ID))
+				.filter_map(|group_elem| group_elem.find("TrackItems"))
+				.map(|track_items| track_items
+					.children()
+					.filter_map(|track_item| track_item.attr("ObjectRef"))
+					.filter_map(|id| id.parse().ok())
+					.collect())
+				.collect())
+			.collect()
 	}
 
+	/// Ties together the cuts of every sequence by their `Link`s and
+	/// groups (see `cut_groups`), builds the timelines and links the
+	/// transitions, then flattens nested sequences if asked to. Unlike parsing
+	/// this doesn't need the XML, so with the `rayon` feature the sequences
+	/// are resolved in parallel.
+	fn resolve_sequences(&mut self, ties: Vec<(CutGroups, CutGroups)>) {
+		self.each_sequence(ties, |seq, (links, groups)| {
+			for link in links {
+				seq.link_cuts(&link);
+			}
+			for group in groups {
+				seq.group_cuts(&group);
+			}
+			seq.resolve_timeline();
+		});
+		if self.flatten_nested {
+			// Replaces cuts of nested sequences with the media cuts they consist of
+			let flattened: Vec<Vec<Cut>> = {
+				let sequences: Vec<&PremiereSequence> = self.sequences.iter().collect();
+				self.map_sequences(|seq| seq.flatten_cuts(&sequences, &mut Vec::new()))
+			};
+			self.each_sequence(flattened, PremiereSequence::set_cuts);
+		}
+	}
+
+	#[cfg(feature = "rayon")]
+	fn each_sequence<T: Send>(&mut self, data: Vec<T>, f: impl Fn(&mut PremiereSequence, T) + Send + Sync) {
+		if self.parallel {
+			self.sequences.par_iter_mut().zip(data).for_each(|(seq, data)| f(seq, data));
+		} else {
+			self.sequences.iter_mut().zip(data).for_each(|(seq, data)| f(seq, data));
+		}
+	}
+
+	#[cfg(not(feature = "rayon"))]
+	fn each_sequence<T>(&mut self, data: Vec<T>, f: impl Fn(&mut PremiereSequence, T)) {
+		self.sequences.iter_mut().zip(data).for_each(|(seq, data)| f(seq, data));
+	}
 
+	#[cfg(feature = "rayon")]
+	fn map_sequences<T: Send>(&self, f: impl Fn(&PremiereSequence) -> T + Send + Sync) -> Vec<T> {
+		if self.parallel {
+			self.sequences.par_iter().map(f).collect()
+		} else {
+			self.sequences.iter().map(f).collect()
+		}
+	}
+
+	#[cfg(not(feature = "rayon"))]
+	fn map_sequences<T>(&self, f: impl Fn(&PremiereSequence) -> T) -> Vec<T> {
+		self.sequences.iter().map(f).collect()
+	}
+
+	/// Parses the track groups each sequence refers to,
+	/// in the order they're in the project.
 	fn resolve_groups(&mut self, id_refs: &HashMapWithVector) -> Result<(), Error> {
-		let mut groups_to_find: usize = id_refs.length();
 		let mut errors: Vec<Error> = Vec::new();
-		for child in self.root.children() {
-			match child.name() {
-				// Only videos for now, since audio is useless for my use-case
-				"VideoTrackGroup" => {
-					if let Some(object_id) = child.attr("ObjectID") {
-						groups_to_find -= 1;
-						for seq_index in id_refs.keys() {
-							if let Some(refs) = id_refs.get(seq_index) {
-								for id_ref in refs {
-									if object_id == id_ref {
-										let seq =
-											self.sequences[seq_index.to_owned()].borrow_mut();
-										if let Err(err) = self.parse_video_track_group(&child, seq) {
-											errors.push(err);
-										}
-									}
-								}
-							}
-						}
-					} else {
-						errors.push(
-							Error::NotFound(
-								NotFoundError::Attribute(
-									NotFoundErrorData::new(
-										String::from("ObjectID"),
-										child.to_owned(),
-									)
+		// Parsing reads from `self.root` while it fills these
+		let mut sequences = std::mem::take(&mut self.sequences);
+		let mut media = std::mem::take(&mut self.media);
+		for (seq_index, seq) in sequences.iter_mut().enumerate() {
+			let refs = match id_refs.get(&seq_index) {
+				Some(refs) => refs,
+				None => continue
+			};
+			let mut positions: Vec<usize> = Vec::new();
+			for id_ref in refs {
+				match self.index.position(id_ref, FindWith::ID) {
+					Some(position) => positions.push(position),
+					None => errors.push(
+						Error::NotFound(
+							NotFoundError::Element(
+								NotFoundErrorData::new(
+									format!("Track group with id: \"{}\"", id_ref),
+									self.root.clone(),
 								)
 							)
 						)
-					}
+					)
 				}
-				_ => {
-					if groups_to_find == 0 {
-						break;
-					}
+			}
+			positions.sort_unstable();
+			for child in positions.into_iter().filter_map(|position| self.object_at(position)) {
+				let result = match child.name() {
+					"VideoTrackGroup" => self.parse_video_track_group(child, seq, &mut media),
+					"AudioTrackGroup" => self.parse_audio_track_group(child, seq, &mut media),
+					_ => continue
+				};
+				if let Err(err) = result {
+					errors.push(err);
 				}
 			}
 		}
-		assert_ne!(id_refs.length(), groups_to_find);
+		self.sequences = sequences;
+		self.media = media;
 
-		let all_errors = errors.len() == id_refs.length();
+		let all_errors = !errors.is_empty() && errors.len() == id_refs.length();
 		// It's ok as long as not all failed.
 		if !all_errors {
 			let errors_len = errors.len();
 			if errors_len > 0 {
-				println!("Ignoring {} errors in resolving video track groups:", errors_len);
+				eprintln!("Ignoring {} errors in resolving track groups:", errors_len);
 				for err in errors {
-					println!("{:#?}", err);
+					eprintln!("{:#?}", err);
 				}
 			}
 			Ok(())
@@ -403,16 +934,15 @@ impl PremiereReader {
 		}
 	}
 
-	fn get_elems_with_ids<'a>(&'a self, identifiers: &'a [&'a str], find_with: FindWith)
-	                          -> impl Iterator<Item = &Element> + 'a
-	{
-		self.root.children().filter(move |elem: &&Element| {
-			if let Some(attr) = elem.attr(find_with.value()) {
-				identifiers.contains(&attr)
-			} else {
-				false
-			}
-		})
+	/// The objects with the given IDs in the order they're in the project.
+	fn get_elems_with_ids(&self, identifiers: &[&str], find_with: FindWith) -> Vec<&Element> {
+		let mut positions: Vec<usize> = identifiers
+			.iter()
+			.filter_map(|identifier| self.index.position(identifier, find_with))
+			.collect();
+		positions.sort_unstable();
+		positions.dedup();
+		positions.into_iter().filter_map(|position| self.object_at(position)).collect()
 	}
 
 	pub(crate) fn get_elems_with_names<'a>(in_elem: &'a Element, names: &[&str])
@@ -429,18 +959,17 @@ impl PremiereReader {
 				}
 			}
 		}
-		println!("Some elements were not found!\nFound: {:#?}\nSearched: {:#?}", vec, names);
+		eprintln!("Some elements were not found!\nFound: {:#?}\nSearched: {:#?}", vec, names);
 		vec
 	}
 
 	fn try_get_elem_with_id(&self, identifier: &str, find_with: FindWith) -> Option<&Element> {
-		for child in self.root.children() {
-			if let Some(attr) = child.attr(find_with.value()) {
-				if attr == identifier {
-					return Some(child);
-				}
-			}
-		}
-		None
+		self.index
+			.position(identifier, find_with)
+			.and_then(|position| self.object_at(position))
+	}
+
+	fn object_at(&self, position: usize) -> Option<&Element> {
+		self.root.nodes().as_slice().get(position).and_then(|node| node.as_element())
 	}
 }
@@ -1,7 +1,7 @@
-use std::cell::RefCell;
 use std::time::Duration;
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
-use super::{Cuts, Size};
+use super::{Cut, Cuts, CutSource, Marker, SequenceSettings, Size, TrackKind, Transition};
 use itertools::Itertools;
 #[cfg(target_arch = "wasm32")]
 use {
@@ -11,23 +11,47 @@ use {
 };
 
 use crate::element::{Element, ElementGetExt};
+use crate::timecode::Timecode;
 use crate::timeline::Timeline;
-use crate::errors::Error;
+use crate::errors::{Error, InvalidValueData};
 use crate::{PremiereReader, TICKS_PER_SECOND};
 use crate::sorted_vec;
 
-pub type PremiereSequences = Vec<RefCell<PremiereSequence>>;
+pub type PremiereSequences = Vec<PremiereSequence>;
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PremiereSequence {
 	pub id: u32,
-	name: String, // TODO: include
-	duration: Duration, // TODO: include
+	/// `ObjectUID` used by clips to refer to this sequence when it's nested.
+	pub(crate) uid: String,
+	pub(crate) name: String,
+	/// Work area bar in seconds.
+	work_area: (f64, f64),
+	/// Sequence in and out marks in seconds.
+	in_mark: Option<f64>,
+	out_mark: Option<f64>,
+	/// Start time in ticks, the time shown at the beginning of the timeline.
+	zero_point: u64,
 	pub(crate) track_groups: Vec<String>,
-	pub(crate) cuts: Cuts, // TODO: include
-	pub(crate) timeline: Timeline, // TODO: include
+	pub(crate) cuts: Cuts,
+	pub(crate) timeline: Timeline,
+	pub(crate) transitions: Vec<Transition>,
+	/// Tracks turned off with *Toggle Track Output* or *Mute Track*.
+	pub(crate) muted_tracks: Vec<(TrackKind, usize)>,
+	/// Markers of the sequence, read from the clip of its master clip.
+	pub(crate) markers: Vec<Marker>,
+	/// `ObjectRef`s of the `Link`s grouping clips of this sequence.
+	/// Only needed while reading, afterwards they're in `Cut::linked`.
+	#[cfg_attr(feature = "serde", serde(skip))]
+	pub(crate) links: Vec<String>,
+	/// `ObjectRef`s of the groups made with *Group* in the timeline.
+	/// Only needed while reading, afterwards they're in `Cut::grouped`.
+	#[cfg_attr(feature = "serde", serde(skip))]
+	pub(crate) groups: Vec<String>,
 	pub size: Size,
+	pub(crate) settings: SequenceSettings,
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -43,6 +67,12 @@ impl PremiereSequence {
 	}
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl PremiereSequence {
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+}
 
 impl Hash for PremiereSequence {
 	fn hash<H: Hasher>(&self, state: &mut H) {
@@ -60,8 +90,13 @@ impl Eq for PremiereSequence {}
 
 impl PremiereSequence {
 	pub fn new(elem: &Element) -> Result<Self, Error> {
-		let mut new_seq = PremiereSequence::default();
-		let mut length: f64 = 0.;
+		Self::with_settings(elem, SequenceSettings::default())
+	}
+
+	/// Like `new` but starting from the given settings, e.g. the project's defaults,
+	/// which are overridden by the ones stored in the sequence.
+	pub fn with_settings(elem: &Element, settings: SequenceSettings) -> Result<Self, Error> {
+		let mut new_seq = PremiereSequence { settings, ..PremiereSequence::default() };
 
 		let (id_elem, name_elem, node_elem, track_groups_elem)
 			: (&Element, &Element, &Element, &Element)
@@ -71,25 +106,28 @@ impl PremiereSequence {
 		).into_iter().tuples().next().unwrap();
 
 		new_seq.name = name_elem.text();
+		new_seq.uid = elem.attr("ObjectUID").unwrap_or_default().to_owned();
 		let properties = node_elem.get("Properties")?;
-		let mut node_count = sorted_vec!["MZ.WorkInPoint", "MZ.WorkOutPoint"].len();
-		let mut work_in_point: u64 = 0;
-		let mut work_out_point: u64 = 0;
+		let seconds = |ticks: i64| ticks as f64 / TICKS_PER_SECOND as f64;
+		// Marks which aren't set are stored as -1 or left out
+		let mark = |child: &Element| child.text()
+			.parse::<i64>()
+			.ok()
+			.filter(|ticks| *ticks >= 0)
+			.map(seconds);
 		for child in properties.children() {
 			match child.name() {
 				"MZ.WorkInPoint" => {
-					work_in_point = child.text().parse().expect("No MZ.WorkInPoint");
-					node_count -= 1;
+					new_seq.work_area.0 = seconds(child.text().parse().expect("No MZ.WorkInPoint"));
 				}
 				"MZ.WorkOutPoint" => {
-					work_out_point = child.text().parse().expect("No MZ.WorkOutPoint");
-					node_count -= 1;
+					new_seq.work_area.1 = seconds(child.text().parse().expect("No MZ.WorkOutPoint"));
 				}
+				"MZ.InPoint" => new_seq.in_mark = mark(child),
+				"MZ.OutPoint" => new_seq.out_mark = mark(child),
+				"MZ.ZeroPoint" => new_seq.zero_point = child.text().parse().unwrap_or(0),
 				_ => {
-					if node_count == 0 {
-						length = (work_out_point - work_in_point) as f64 / TICKS_PER_SECOND as f64;
-						break;
-					}
+					new_seq.settings.read_property(child);
 				}
 			}
 		}
@@ -103,17 +141,292 @@ impl PremiereSequence {
 				}
 			}
 		}
-		new_seq.id = id_elem.text().parse().unwrap();
-
-		new_seq.duration = Duration::from_secs_f64(length);
+		if let Some(links) = elem.find("LinkContainer").and_then(|container| container.find("Links")) {
+			for link in links.children() {
+				new_seq.links.push(link.get_attr("ObjectRef")?.to_owned());
+			}
+		}
+		if let Some(groups) = elem.find("GroupContainer").and_then(|container| container.find("Groups")) {
+			for group in groups.children() {
+				new_seq.groups.push(group.get_attr("ObjectRef")?.to_owned());
+			}
+		}
+		if let Ok(preview_format) = elem.get("PreviewFormatIdentifier") {
+			new_seq.settings.preview.format = preview_format.text();
+		}
+		let id = id_elem.text();
+		new_seq.id = id.parse().map_err(|_| Error::Invalid(InvalidValueData::new(String::from("ID"), id)))?;
 		Ok(new_seq)
 	}
+
+	/// Start and end of the work area bar in seconds.
+	pub fn work_area(&self) -> (f64, f64) {
+		self.work_area
+	}
+
+	pub fn work_area_duration(&self) -> Duration {
+		Duration::from_secs_f64((self.work_area.1 - self.work_area.0).max(0.))
+	}
+
+	/// In mark of the sequence in seconds, if it's set.
+	pub fn in_mark(&self) -> Option<f64> {
+		self.in_mark
+	}
+
+	/// Out mark of the sequence in seconds, if it's set.
+	pub fn out_mark(&self) -> Option<f64> {
+		self.out_mark
+	}
+
+	/// End of the last clip on any track in seconds.
+	pub fn end(&self) -> f64 {
+		self.cuts
+			.iter()
+			.map(|cut| cut.end)
+			.fold(0., f64::max)
+	}
+
+	/// Length of the sequence up to the end of its last clip.
+	pub fn duration(&self) -> Duration {
+		Duration::from_secs_f64(self.end())
+	}
+
+	/// Time shown at the beginning of the timeline in seconds, e.g. `3600.` for 01:00:00:00.
+	pub fn start_time(&self) -> f64 {
+		self.zero_point as f64 / TICKS_PER_SECOND as f64
+	}
+
+	pub fn start_timecode(&self) -> Timecode {
+		self.timecode_at(0.)
+	}
+
+	/// Timecode shown at `time` seconds of the timeline, counting from the start time.
+	pub fn timecode_at(&self, time: f64) -> Timecode {
+		Timecode::from_seconds(self.start_time() + time, self.settings.fps(), self.settings.drop_frame)
+	}
+
+	pub fn settings(&self) -> &SequenceSettings {
+		&self.settings
+	}
+
+	pub fn cuts(&self) -> &Cuts {
+		&self.cuts
+	}
+
+	/// The parts of video cuts which are visible, cuts on higher tracks
+	/// hiding the ones below.
+	pub fn timeline(&self) -> &Timeline {
+		&self.timeline
+	}
+
+	/// Transitions of all tracks.
+	pub fn transitions(&self) -> &[Transition] {
+		&self.transitions
+	}
+
+	pub fn transitions_on(&self, kind: TrackKind, track: usize) -> impl Iterator<Item = &Transition> {
+		self.transitions
+			.iter()
+			.filter(move |transition| transition.kind == kind && transition.track == track)
+	}
+
+	/// Markers of the sequence sorted by time, times are seconds of the timeline.
+	pub fn markers(&self) -> &[Marker] {
+		&self.markers
+	}
+
+	pub fn is_track_muted(&self, kind: TrackKind, track: usize) -> bool {
+		self.muted_tracks.contains(&(kind, track))
+	}
+
+	/// Cuts linked to the given one, e.g. the audio of a video clip.
+	pub fn linked_cuts<'a>(&'a self, cut: &'a Cut) -> impl Iterator<Item = &'a Cut> + 'a {
+		self.cuts
+			.iter()
+			.filter(move |other| cut.linked.contains(&other.id))
+	}
+
+	/// Cuts grouped with the given one, see `Cut::grouped`.
+	pub fn grouped_cuts<'a>(&'a self, cut: &'a Cut) -> impl Iterator<Item = &'a Cut> + 'a {
+		self.cuts
+			.iter()
+			.filter(move |other| cut.grouped.contains(&other.id))
+	}
+
+	/// Links the cuts whose IDs are in the same `Link` with each other.
+	pub(crate) fn link_cuts(&mut self, link: &[u32]) {
+		self.tie_cuts(link, |cut| &mut cut.linked);
+	}
+
+	/// Groups the cuts whose IDs are in the same group with each other.
+	pub(crate) fn group_cuts(&mut self, group: &[u32]) {
+		self.tie_cuts(group, |cut| &mut cut.grouped);
+	}
+
+	/// Adds the IDs of the other cuts in `ids` to the list `ties` picks
+	/// from each of the cuts in `ids`.
+	fn tie_cuts(&mut self, ids: &[u32], ties: impl Fn(&mut Cut) -> &mut Vec<u32>) {
+		for cut in self.cuts.cuts.iter_mut() {
+			if ids.contains(&cut.id) {
+				let own_id = cut.id;
+				let tied = ties(cut);
+				for id in ids.iter().filter(|id| **id != own_id) {
+					if !tied.contains(id) {
+						tied.push(*id);
+					}
+				}
+			}
+		}
+	}
+
+	/// Cuts on the given track with their indices in `cuts`.
+	pub(crate) fn cuts_on(&self, kind: TrackKind, track: usize) -> Vec<(usize, &Cut)> {
+		self.cuts
+			.iter()
+			.enumerate()
+			.filter(|(_, cut)| cut.kind == kind && cut.track == track)
+			.collect()
+	}
+
+	/// Replaces all of the cuts, rebuilding the timeline
+	/// and the links of transitions to cuts.
+	pub(crate) fn set_cuts(&mut self, cuts: Vec<Cut>) {
+		self.cuts = Cuts { cuts };
+		self.resolve_timeline();
+	}
+
+	/// Builds the timeline from the cuts and links the transitions to them.
+	pub(crate) fn resolve_timeline(&mut self) {
+		self.timeline = Timeline::default();
+		for (index, cut) in self.cuts.cuts.iter().enumerate() {
+			if cut.kind == TrackKind::Video && !cut.disabled {
+				self.timeline.add(index, cut.start, cut.end);
+			}
+		}
+		let mut transitions = std::mem::take(&mut self.transitions);
+		for transition in transitions.iter_mut() {
+			transition.link(&self.cuts_on(transition.kind, transition.track));
+		}
+		self.transitions = transitions;
+	}
+
+	/// Expands cuts whose source is another sequence into the cuts
+	/// of that sequence which fall into the used range,
+	/// shifted to the position of the nested sequence on this timeline.
+	/// `visited` holds the IDs of the sequences being expanded, so that
+	/// a sequence nesting itself doesn't recurse forever.
+	///
+	/// Expanded cuts get IDs following the highest one of this sequence, so
+	/// a sequence nested twice doesn't repeat IDs, and their links and groups
+	/// are moved to these. Their tracks count from the track of the nested
+	/// clip, e.g. V2 of the nested sequence ends up on V3 when it's on V2.
+	/// A cut only expands into the cuts of its own kind: Premiere nests
+	/// a sequence as a video clip and an audio clip, the latter playing
+	/// the audio of the sequence.
+	pub(crate) fn flatten_cuts(&self, sequences: &[&PremiereSequence], visited: &mut Vec<u32>) -> Vec<Cut> {
+		visited.push(self.id);
+		let mut next_id = self.cuts.iter().map(|cut| cut.id).max().map_or(0, |id| id + 1);
+		// IDs of the nested cuts with the IDs of the cuts they expanded into
+		let mut expanded: HashMap<u32, Vec<u32>> = HashMap::new();
+		let mut flat: Vec<Cut> = Vec::new();
+		for cut in self.cuts.iter() {
+			let nested = match cut.source {
+				CutSource::Sequence(id) if !visited.contains(&id) =>
+					sequences.iter().find(|seq| seq.id == id),
+				_ => None
+			};
+			let nested = match nested {
+				Some(nested) => nested,
+				None => {
+					flat.push(cut.clone());
+					continue;
+				}
+			};
+			// Part of the nested sequence played back by the cut
+			let (used_from, used_to) = cut.source_range();
+			let rate = cut.speed.rate;
+			// Time remapping of the nested clip is approximated with its base rate
+			let to_timeline = |nested_time: f64| if cut.speed.reverse {
+				cut.start + (used_to - nested_time) / rate
+			} else {
+				cut.start + (nested_time - used_from) / rate
+			};
+			let first = flat.len();
+			let mut ids: HashMap<u32, u32> = HashMap::new();
+			for inner in nested.flatten_cuts(sequences, visited) {
+				if inner.kind != cut.kind {
+					continue;
+				}
+				let from = inner.start.max(used_from);
+				let to = inner.end.min(used_to);
+				if from >= to {
+					continue;
+				}
+				let (start, end) = if cut.speed.reverse {
+					(to_timeline(to), to_timeline(from))
+				} else {
+					(to_timeline(from), to_timeline(to))
+				};
+				let (source_from, source_to) = (inner.source_time_at(from), inner.source_time_at(to));
+				// Effects of the nested clip apply on top of the inner ones
+				let mut effects = inner.effects;
+				effects.extend(cut.effects.iter().cloned());
+				let mut speed = inner.speed;
+				speed.rate *= rate;
+				speed.reverse ^= cut.speed.reverse;
+				ids.insert(inner.id, next_id);
+				flat.push(Cut {
+					id: next_id,
+					start,
+					end,
+					in_point: source_from.min(source_to),
+					out_point: source_from.max(source_to),
+					kind: cut.kind,
+					track: cut.track + inner.track,
+					source: inner.source,
+					effects,
+					speed,
+					linked: inner.linked,
+					grouped: inner.grouped,
+					disabled: inner.disabled || cut.disabled,
+					markers: inner.markers,
+				});
+				next_id += 1;
+			}
+			// Ties within the nested sequence move to the new IDs, the ones
+			// of the nested clip are kept and moved once all are expanded
+			let retie = |inner: &[u32], outer: &[u32]| inner
+				.iter()
+				.filter_map(|id| ids.get(id).copied())
+				.chain(outer.iter().copied())
+				.collect::<Vec<u32>>();
+			for flattened in flat[first..].iter_mut() {
+				flattened.linked = retie(&flattened.linked, &cut.linked);
+				flattened.grouped = retie(&flattened.grouped, &cut.grouped);
+			}
+			expanded.insert(cut.id, flat[first..].iter().map(|flattened| flattened.id).collect());
+		}
+		// Cuts tied to a nested clip are tied to what it expanded into
+		for cut in flat.iter_mut() {
+			let own_id = cut.id;
+			let retie = |tied: &[u32]| tied
+				.iter()
+				.flat_map(|id| expanded.get(id).cloned().unwrap_or_else(|| vec![*id]))
+				.filter(|id| *id != own_id)
+				.unique()
+				.collect::<Vec<u32>>();
+			cut.linked = retie(&cut.linked);
+			cut.grouped = retie(&cut.grouped);
+		}
+		visited.pop();
+		flat
+	}
 }
 
 trait SequenceErrors {
 	fn name(&self) -> &str;
 	fn print_has_no(&self, x: &str) {
-		println!("<{}> has no <{}> element!", self.name(), x);
+		eprintln!("<{}> has no <{}> element!", self.name(), x);
 	}
 }
 
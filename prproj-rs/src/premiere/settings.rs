@@ -0,0 +1,216 @@
+use std::fmt;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use crate::element::{Element, ElementGetExt};
+use crate::TICKS_PER_SECOND;
+use super::Size;
+
+/// Four character code as used for codecs and file types,
+/// e.g. `MPIN` for I-Frame Only MPEG previews.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct FourCC(pub u32);
+
+impl fmt::Display for FourCC {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let bytes = self.0.to_be_bytes();
+		if bytes.iter().all(|byte| byte.is_ascii_graphic() || *byte == b' ') {
+			write!(f, "{}", String::from_utf8_lossy(&bytes))
+		} else {
+			write!(f, "{}", self.0)
+		}
+	}
+}
+
+/// Order of the fields of interlaced video, `FieldType` in the project file.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FieldOrder {
+	#[default]
+	Progressive,
+	UpperFirst,
+	LowerFirst,
+}
+
+impl From<u32> for FieldOrder {
+	fn from(code: u32) -> Self {
+		match code {
+			1 => FieldOrder::UpperFirst,
+			2 => FieldOrder::LowerFirst,
+			_ => FieldOrder::Progressive
+		}
+	}
+}
+
+/// Channel layout of the audio master track, `ChannelType` in the project file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ChannelType {
+	Mono,
+	#[default]
+	Stereo,
+	Surround51,
+	Multichannel,
+	Other(u32),
+}
+
+impl From<u32> for ChannelType {
+	fn from(code: u32) -> Self {
+		match code {
+			0 => ChannelType::Mono,
+			1 => ChannelType::Stereo,
+			2 => ChannelType::Surround51,
+			3 => ChannelType::Multichannel,
+			other => ChannelType::Other(other)
+		}
+	}
+}
+
+/// Format of the preview files rendered for a sequence.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PreviewSettings {
+	/// `PreviewFormatIdentifier`, the editing mode the previews are made for.
+	pub format: String,
+	/// Kind of file, e.g. `MPGI`.
+	pub file_type: FourCC,
+	/// Video codec, e.g. `MPIN`.
+	pub codec: FourCC,
+	pub audio_codec: FourCC,
+	/// Path of the encoder preset, relative to Premiere's presets.
+	pub preset_path: String,
+	pub frame_size: Size,
+	pub max_bit_depth: bool,
+	pub max_render_quality: bool,
+}
+
+/// Settings of a sequence as in Premiere's *Sequence Settings* dialog.
+///
+/// Values missing from a sequence are taken from the project's
+/// `VideoSettings`, `AudioSettings` and compile settings.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SequenceSettings {
+	/// Timebase in ticks per frame, like `PremiereMedium::frame_rate`.
+	pub frame_rate: u64,
+	pub frame_size: Size,
+	/// Width and height of a pixel, `(1, 1)` for square pixels.
+	pub pixel_aspect_ratio: (u32, u32),
+	pub field_order: FieldOrder,
+	/// Whether timecode is displayed drop-frame.
+	pub drop_frame: bool,
+	/// Samples per second.
+	pub audio_sample_rate: u32,
+	pub audio_channels: ChannelType,
+	pub preview: PreviewSettings,
+}
+
+impl SequenceSettings {
+	pub fn fps(&self) -> f64 {
+		crate::timecode::fps(self.frame_rate)
+	}
+
+	/// Reads the `<VideoSettings>` of the project or of compile settings.
+	pub(crate) fn read_video_settings(&mut self, video_settings: &Element) {
+		if let Ok(frame_rate) = video_settings.get("FrameRate") {
+			self.frame_rate = frame_rate.text().parse().unwrap_or(self.frame_rate);
+		}
+		if let Some(size) = video_settings.get("FrameSize").ok().and_then(|size| parse_rect(&size.text())) {
+			self.frame_size = size;
+		}
+		self.read_pixel_aspect_ratio(video_settings);
+		if let Ok(max_bit_depth) = video_settings.get("MaximumBitDepth") {
+			self.preview.max_bit_depth = max_bit_depth.text() == "true";
+		}
+	}
+
+	pub(crate) fn read_audio_settings(&mut self, audio_settings: &Element) {
+		if let Ok(frame_rate) = audio_settings.get("FrameRate") {
+			self.read_audio_frame_rate(frame_rate);
+		}
+		if let Ok(channel_type) = audio_settings.get("ChannelType") {
+			self.read_channel_type(channel_type);
+		}
+	}
+
+	pub(crate) fn read_video_compile_settings(&mut self, compile_settings: &Element) {
+		if let Ok(file_type) = compile_settings.get("VideoFileTypeFourCC") {
+			self.preview.file_type = parse_fourcc(file_type);
+		}
+		if let Ok(compressor) = compile_settings.get("Compressor") {
+			self.preview.codec = parse_fourcc(compressor);
+		}
+		if let Ok(field_type) = compile_settings.get("VideoFieldType") {
+			self.field_order = field_type.text().parse::<u32>().unwrap_or(0).into();
+		}
+	}
+
+	pub(crate) fn read_audio_compile_settings(&mut self, compile_settings: &Element) {
+		if let Ok(compressor) = compile_settings.get("Compressor") {
+			self.preview.audio_codec = parse_fourcc(compressor);
+		}
+	}
+
+	/// Reads a `MZ.Sequence.*` property of a sequence,
+	/// returns whether it was one.
+	pub(crate) fn read_property(&mut self, property: &Element) -> bool {
+		let text = property.text();
+		match property.name() {
+			"MZ.Sequence.PreviewRenderingClassID" => self.preview.file_type = parse_fourcc(property),
+			"MZ.Sequence.PreviewRenderingPresetCodec" => self.preview.codec = parse_fourcc(property),
+			"MZ.Sequence.PreviewRenderingPresetPath" => self.preview.preset_path = text,
+			"MZ.Sequence.PreviewFrameSizeWidth" =>
+				self.preview.frame_size.width = text.parse().unwrap_or_default(),
+			"MZ.Sequence.PreviewFrameSizeHeight" =>
+				self.preview.frame_size.height = text.parse().unwrap_or_default(),
+			"MZ.Sequence.PreviewUseMaxBitDepth" => self.preview.max_bit_depth = text == "true",
+			"MZ.Sequence.PreviewUseMaxRenderQuality" => self.preview.max_render_quality = text == "true",
+			// 102 and 106 are 29.97 and 59.94 drop-frame
+			"MZ.Sequence.VideoTimeDisplayFormat" => self.drop_frame = text == "102" || text == "106",
+			_ => return false
+		}
+		true
+	}
+
+	pub(crate) fn read_pixel_aspect_ratio(&mut self, parent: &Element) {
+		let ratio = parent.get("PixelAspectRatio").map(|ratio| ratio.text()).unwrap_or_default();
+		let mut parts = ratio.split(',').map(|part| part.trim().parse::<u32>());
+		if let (Some(Ok(width)), Some(Ok(height))) = (parts.next(), parts.next()) {
+			self.pixel_aspect_ratio = (width, height);
+		}
+	}
+
+	/// Audio `<FrameRate>`s are ticks per sample.
+	pub(crate) fn read_audio_frame_rate(&mut self, frame_rate: &Element) {
+		let ticks: u64 = frame_rate.text().parse().unwrap_or(0);
+		if let Some(sample_rate) = TICKS_PER_SECOND.checked_div(ticks) {
+			self.audio_sample_rate = sample_rate as u32;
+		}
+	}
+
+	pub(crate) fn read_channel_type(&mut self, channel_type: &Element) {
+		if let Ok(code) = channel_type.text().parse::<u32>() {
+			self.audio_channels = code.into();
+		}
+	}
+}
+
+/// Parses the size of a rectangle like `0,0,1920,1080`.
+pub(crate) fn parse_rect(text: &str) -> Option<Size> {
+	let mut rect = text.split(',').skip(2).map(|part| part.trim().parse::<u32>());
+	match (rect.next(), rect.next()) {
+		(Some(Ok(width)), Some(Ok(height))) => Some(Size { width, height }),
+		_ => None
+	}
+}
+
+fn parse_fourcc(elem: &Element) -> FourCC {
+	FourCC(elem.text().trim().parse().unwrap_or_default())
+}
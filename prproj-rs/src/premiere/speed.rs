@@ -0,0 +1,121 @@
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use crate::element::{Element, ElementGetExt};
+use super::effect::{Effect, ParamValue};
+use super::keyframe::{self, Keyframe};
+
+/// How frames are made up when a clip doesn't play at 100%.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TimeInterpolation {
+	#[default]
+	FrameSampling,
+	FrameBlending,
+	OpticalFlow,
+}
+
+/// Playback speed of a clip set with *Speed/Duration* or *Time Remapping*.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Speed {
+	/// `1.0` is 100%, always positive.
+	pub rate: f64,
+	pub reverse: bool,
+	pub interpolation: TimeInterpolation,
+	/// Keyframes of the *Time Remapping > Speed* parameter in percent,
+	/// empty when the speed is constant.
+	pub remap: Vec<Keyframe>,
+}
+
+impl Default for Speed {
+	fn default() -> Self {
+		Self {
+			rate: 1.,
+			reverse: false,
+			interpolation: TimeInterpolation::default(),
+			remap: Vec::new(),
+		}
+	}
+}
+
+impl Speed {
+	/// Reads the speed of the `<Clip>` of a subclip, the time interpolation
+	/// of the clip track item and the time remapping among the clip's `effects`.
+	pub(crate) fn new(clip: &Element, clip_track_item: &Element, effects: &[Effect]) -> Self {
+		let mut speed = Speed::default();
+		if let Ok(playback_speed) = clip.get("PlaybackSpeed") {
+			let rate: f64 = playback_speed.text().trim().parse().unwrap_or(1.);
+			speed.reverse = rate < 0.;
+			speed.rate = rate.abs();
+		}
+		if let Some(reverse) = clip.find("Reverse") {
+			speed.reverse |= reverse.text() == "true";
+		}
+
+		if let Some(frame_blend) = clip_track_item.find("FrameBlend") {
+			if frame_blend.text() == "true" {
+				speed.interpolation = TimeInterpolation::FrameBlending;
+			}
+		}
+		if let Some(interpolation) = clip_track_item.find("TimeInterpolationType") {
+			speed.interpolation = match interpolation.text().trim() {
+				"1" => TimeInterpolation::FrameBlending,
+				"2" => TimeInterpolation::OpticalFlow,
+				_ => TimeInterpolation::FrameSampling
+			};
+		}
+
+		speed.remap = effects
+			.iter()
+			.filter(|effect| effect.display_name == "Time Remapping" || effect.match_name.contains("Time Remap"))
+			.filter_map(|effect| effect.param("Speed"))
+			.map(|param| param.keyframes.to_owned())
+			.find(|keyframes| !keyframes.is_empty())
+			.unwrap_or_default();
+		speed
+	}
+
+	pub fn is_constant(&self) -> bool {
+		self.remap.is_empty()
+	}
+
+	/// Rate at `time` seconds of the clip, that is `Cut::in_point`
+	/// plus the seconds since the cut started, like for other keyframes.
+	pub fn rate_at(&self, time: f64) -> f64 {
+		match keyframe::evaluate(&self.remap, time) {
+			Some(ParamValue::Number(percent)) => percent / 100.,
+			_ => self.rate
+		}
+	}
+
+	/// Seconds of source played back during the first `elapsed` seconds
+	/// of a cut starting at `in_point`.
+	pub fn source_elapsed(&self, in_point: f64, elapsed: f64) -> f64 {
+		if self.is_constant() {
+			return elapsed * self.rate;
+		}
+		// The rate is linear between keyframes (or held), so integrating
+		// segment by segment between the keyframes is exact.
+		let (from, to) = (in_point, in_point + elapsed);
+		let mut points: Vec<f64> = vec![from];
+		points.extend(
+			self.remap
+				.iter()
+				.map(|keyframe| keyframe.time)
+				.filter(|time| *time > from && *time < to)
+		);
+		points.push(to);
+		points
+			.windows(2)
+			.map(|segment| {
+				let (a, b) = (segment[0], segment[1]);
+				// Just before `b`, so a held value doesn't jump to the next keyframe
+				let before_b = b - (b - a) * 1e-9;
+				(self.rate_at(a) + self.rate_at(before_b)) / 2. * (b - a)
+			})
+			.sum()
+	}
+}
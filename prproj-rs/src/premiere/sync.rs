@@ -0,0 +1,55 @@
+use super::{PremiereSequence, TrackKind};
+use crate::timecode::{self, Timecode};
+
+/// A linked audio clip which doesn't line up with its video anymore,
+/// what Premiere shows as red sync badges.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SyncSlip {
+	/// `PremiereSequence::id` of the sequence the clips are in.
+	pub sequence: u32,
+	pub sequence_name: String,
+	/// `Cut::id` and track index of the video clip.
+	pub video_cut: u32,
+	pub video_track: usize,
+	/// `Cut::id` and track index of the audio clip.
+	pub audio_cut: u32,
+	pub audio_track: usize,
+	/// Frames the audio lags behind the video, negative when it's ahead.
+	pub offset: i64,
+	/// Where the video clip starts in the sequence.
+	pub timecode: Timecode,
+}
+
+impl PremiereSequence {
+	/// Lists every linked video and audio pair whose distance on the timeline
+	/// differs from their distance in the source by at least one frame.
+	/// Video clips saved with a speed of 0 are taken to play at 100%.
+	pub fn out_of_sync(&self) -> Vec<SyncSlip> {
+		let fps = self.settings.fps();
+		let mut slips = Vec::new();
+		for video in self.cuts.iter().filter(|cut| cut.kind == TrackKind::Video) {
+			for audio in self.linked_cuts(video).filter(|cut| cut.kind == TrackKind::Audio) {
+				let timeline_offset = audio.start - video.start;
+				// Linked clips change speed together, a rate of 0 is taken as unknown
+				let rate = if video.speed().rate > 0. { video.speed().rate } else { 1. };
+				let source_offset = (audio.in_point - video.in_point) / rate;
+				let offset = timecode::to_frames(timeline_offset - source_offset, fps);
+				if offset == 0 {
+					continue;
+				}
+				slips.push(SyncSlip {
+					sequence: self.id,
+					sequence_name: self.name.to_owned(),
+					video_cut: video.id,
+					video_track: video.track,
+					audio_cut: audio.id,
+					audio_track: audio.track,
+					offset,
+					timecode: self.timecode_at(video.start),
+				});
+			}
+		}
+		slips
+	}
+}
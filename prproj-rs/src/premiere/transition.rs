@@ -0,0 +1,95 @@
+use crate::element::{Element, ElementGetExt};
+use super::{Cut, TrackKind};
+
+/// Times closer than this are considered the same edit point.
+const EPSILON: f64 = 1e-9;
+
+/// Where a transition lies relative to the edit point it covers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TransitionAlignment {
+	/// Starts at the cut, on the incoming clip.
+	Start,
+	/// Centered on the cut.
+	Center,
+	/// Ends at the cut, on the outgoing clip.
+	End,
+}
+
+/// A transition (e.g. Cross Dissolve) on a video or audio track.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transition {
+	pub kind: TrackKind,
+	/// Index of the track in its track group, `0` is V1 or A1.
+	pub track: usize,
+	/// Position on the timeline in seconds.
+	pub start: f64,
+	pub end: f64,
+	pub display_name: String,
+	/// e.g. `AE.ADBE Cross Dissolve New`.
+	pub match_name: String,
+	pub alignment: TransitionAlignment,
+	/// Indices into the sequence's cuts of the clips the transition joins.
+	/// Transitions at the head or tail of a clip only have one of them.
+	pub from_cut: Option<usize>,
+	pub to_cut: Option<usize>,
+}
+
+impl Transition {
+	/// `component` is the object referenced from the `TransitionTrackItem`,
+	/// it names the kind of the transition.
+	pub(crate) fn new(kind: TrackKind, track: usize, start: f64, end: f64, component: Option<&Element>) -> Self {
+		let text_of = |name: &str| component
+			.and_then(|component| component.find(name))
+			.map(|found| found.text().trim().to_owned())
+			.filter(|text| !text.is_empty());
+
+		let match_name = text_of("MatchName")
+			.or_else(|| component.map(|component| component.name().to_owned()))
+			.unwrap_or_default();
+		let display_name = text_of("DisplayName")
+			.or_else(|| text_of("InstanceName"))
+			.unwrap_or_else(|| match_name.to_owned());
+
+		Self {
+			kind,
+			track,
+			start,
+			end,
+			display_name,
+			match_name,
+			alignment: TransitionAlignment::Center,
+			from_cut: None,
+			to_cut: None,
+		}
+	}
+
+	pub fn duration(&self) -> f64 {
+		self.end - self.start
+	}
+
+	/// Finds the clips the transition joins among the `cuts` of its track
+	/// (given with their indices in the sequence) and derives the alignment.
+	pub(crate) fn link(&mut self, cuts: &[(usize, &Cut)]) {
+		let within = |time: f64| time >= self.start - EPSILON && time <= self.end + EPSILON;
+		let from = cuts.iter().find(|(_, cut)| within(cut.end) && cut.start < self.start + EPSILON);
+		let to = cuts.iter().find(|(_, cut)| within(cut.start) && cut.end > self.end - EPSILON);
+		self.from_cut = from.map(|(index, _)| *index);
+		self.to_cut = to.map(|(index, _)| *index);
+
+		let edit_point = match (from, to) {
+			(Some((_, from)), _) => from.end,
+			(None, Some((_, to))) => to.start,
+			(None, None) => return
+		};
+		self.alignment = if (edit_point - self.start).abs() < EPSILON {
+			TransitionAlignment::Start
+		} else if (edit_point - self.end).abs() < EPSILON {
+			TransitionAlignment::End
+		} else {
+			TransitionAlignment::Center
+		};
+	}
+}
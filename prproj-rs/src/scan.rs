@@ -0,0 +1,155 @@
+//! Indexes every project below a directory, e.g. to find out
+//! which projects of an archive use a clip.
+//!
+//! Each project is reduced to its sequences and the paths of the media
+//! they play. A project which can't be read, even one making the reader
+//! panic, gets an `error` instead and doesn't stop the scan.
+//! With the `rayon` feature projects are read in parallel.
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::history::AUTO_SAVE_DIR;
+use crate::premiere::{CutSource, PremiereSequence};
+use crate::{PremiereFile, PremiereReader, Reader};
+
+/// A project found by `scan`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProjectIndex {
+	pub path: PathBuf,
+	pub sequences: Vec<SequenceIndex>,
+	/// Why the project couldn't be read, `sequences` is empty then.
+	pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SequenceIndex {
+	pub id: u32,
+	pub name: String,
+	/// Paths of the media the sequence plays, those of nested
+	/// sequences included, sorted and each one once.
+	pub media: Vec<String>,
+}
+
+impl ProjectIndex {
+	/// Whether a sequence of the project plays the file at `path`.
+	pub fn uses(&self, path: &str) -> bool {
+		self.sequences.iter().any(|seq| seq.media.iter().any(|medium| medium == path))
+	}
+}
+
+/// Reads every `.prproj` file below `dir`, see `find_projects`.
+/// Fails only if `dir` itself can't be listed.
+pub fn scan(dir: &Path) -> io::Result<Vec<ProjectIndex>> {
+	let paths = find_projects(dir)?;
+	#[cfg(feature = "rayon")]
+	let projects = paths.par_iter().map(|path| index_project(path)).collect();
+	#[cfg(not(feature = "rayon"))]
+	let projects = paths.iter().map(|path| index_project(path)).collect();
+	Ok(projects)
+}
+
+/// Paths of the `.prproj` files below `dir`, sorted.
+/// Premiere's auto-save folders are left out as they only hold
+/// copies, and so are symbolic links and folders which can't be listed.
+pub fn find_projects(dir: &Path) -> io::Result<Vec<PathBuf>> {
+	let mut projects = Vec::new();
+	let mut dirs = vec![fs::read_dir(dir)?];
+	while let Some(entries) = dirs.pop() {
+		for entry in entries.flatten() {
+			let file_type = match entry.file_type() {
+				Ok(file_type) => file_type,
+				Err(_) => continue
+			};
+			let path = entry.path();
+			if file_type.is_dir() {
+				if entry.file_name() != AUTO_SAVE_DIR {
+					if let Ok(entries) = fs::read_dir(&path) {
+						dirs.push(entries);
+					}
+				}
+			} else if file_type.is_file() && is_project(&path) {
+				projects.push(path);
+			}
+		}
+	}
+	projects.sort();
+	Ok(projects)
+}
+
+fn is_project(path: &Path) -> bool {
+	path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("prproj"))
+}
+
+/// Reads the project at `path`, any error ends up in `ProjectIndex::error`.
+pub fn index_project(path: &Path) -> ProjectIndex {
+	let read = panic::catch_unwind(AssertUnwindSafe(|| read(path)));
+	let (sequences, error) = match read {
+		Ok(Ok(project)) => (sequences(&project), None),
+		Ok(Err(err)) => (Vec::new(), Some(err)),
+		Err(panic) => {
+			let message = panic
+				.downcast_ref::<&str>()
+				.map(|message| message.to_string())
+				.or_else(|| panic.downcast_ref::<String>().cloned())
+				.unwrap_or_else(|| String::from("unknown error"));
+			(Vec::new(), Some(format!("reader panicked: {}", message)))
+		}
+	};
+	ProjectIndex {
+		path: path.to_owned(),
+		sequences,
+		error,
+	}
+}
+
+fn read(path: &Path) -> Result<PremiereFile, String> {
+	let file = File::open(path).map_err(|err| err.to_string())?;
+	let mut reader = PremiereReader::from_reader(file).map_err(|err| err.to_string())?;
+	reader.read().map_err(|err| err.to_string())?;
+	Ok(reader.take())
+}
+
+fn sequences(project: &PremiereFile) -> Vec<SequenceIndex> {
+	project.sequences
+		.iter()
+		.map(|seq| {
+			let mut media = BTreeSet::new();
+			media_of(project, seq, &mut vec![seq.id], &mut media);
+			SequenceIndex {
+				id: seq.id,
+				name: seq.name.to_owned(),
+				media: media.into_iter().map(str::to_owned).collect(),
+			}
+		})
+		.collect()
+}
+
+/// Adds the paths of the media `seq` plays to `media`, `visited`
+/// holds the IDs of the sequences on the way to guard against cycles.
+fn media_of<'a>(project: &'a PremiereFile, seq: &PremiereSequence, visited: &mut Vec<u32>, media: &mut BTreeSet<&'a str>) {
+	for cut in seq.cuts().iter() {
+		match cut.source() {
+			CutSource::Medium(id) => {
+				if let Some(medium) = project.medium(*id) {
+					media.insert(medium.file_path());
+				}
+			}
+			CutSource::Sequence(id) if !visited.contains(id) => {
+				if let Some(nested) = project.sequences.iter().find(|nested| nested.id == *id) {
+					visited.push(*id);
+					media_of(project, nested, visited, media);
+					visited.pop();
+				}
+			}
+			CutSource::Sequence(_) => ()
+		}
+	}
+}
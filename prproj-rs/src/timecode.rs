@@ -0,0 +1,81 @@
+use std::fmt;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use crate::TICKS_PER_SECOND;
+
+/// SMPTE timecode (`HH:MM:SS:FF`) of a position on a timeline.
+///
+/// Drop-frame timecode (`HH:MM:SS;FF`) skips frame numbers at the start of
+/// every minute except each tenth one, so it keeps up with the clock at
+/// 29.97 and 59.94 fps.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timecode {
+	pub hours: u32,
+	pub minutes: u32,
+	pub seconds: u32,
+	pub frames: u32,
+	pub drop_frame: bool,
+}
+
+impl Timecode {
+	/// Timecode of the frame `frames` counted from zero at `fps` frames per second.
+	/// `drop_frame` only has an effect for rates close to 29.97 and 59.94.
+	pub fn from_frames(frames: u64, fps: f64, drop_frame: bool) -> Self {
+		let nominal = fps.round().max(1.) as u64;
+		let drop_frame = drop_frame && nominal.is_multiple_of(30) && (fps - nominal as f64).abs() > 1e-3;
+		let mut frames = frames;
+		if drop_frame {
+			let dropped = nominal / 15;
+			let per_minute = nominal * 60 - dropped;
+			let per_ten_minutes = nominal * 600 - dropped * 9;
+			let tens = frames / per_ten_minutes;
+			let rest = frames % per_ten_minutes;
+			frames += dropped * 9 * tens;
+			if rest > dropped {
+				frames += dropped * ((rest - dropped) / per_minute);
+			}
+		}
+		let total_seconds = frames / nominal;
+		Self {
+			hours: (total_seconds / 3600) as u32,
+			minutes: (total_seconds / 60 % 60) as u32,
+			seconds: (total_seconds % 60) as u32,
+			frames: (frames % nominal) as u32,
+			drop_frame,
+		}
+	}
+
+	/// Timecode of the frame shown at `seconds`.
+	pub fn from_seconds(seconds: f64, fps: f64, drop_frame: bool) -> Self {
+		Self::from_frames(to_frames(seconds, fps).max(0) as u64, fps, drop_frame)
+	}
+}
+
+impl fmt::Display for Timecode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let separator = if self.drop_frame { ';' } else { ':' };
+		write!(
+			f,
+			"{:02}:{:02}:{:02}{}{:02}",
+			self.hours, self.minutes, self.seconds, separator, self.frames
+		)
+	}
+}
+
+/// Frames per second of a frame rate given in ticks per frame,
+/// as stored in `<FrameRate>`. `0` for a rate of `0`.
+pub fn fps(ticks_per_frame: u64) -> f64 {
+	if ticks_per_frame == 0 {
+		return 0.;
+	}
+	TICKS_PER_SECOND as f64 / ticks_per_frame as f64
+}
+
+/// Number of the frame shown at `seconds`, rounded to the nearest one.
+pub fn to_frames(seconds: f64, fps: f64) -> i64 {
+	(seconds * fps).round() as i64
+}
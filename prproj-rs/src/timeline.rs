@@ -16,6 +16,8 @@ use itertools::Itertools;
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 // #[cfg_attr(target_arch = "wasm32", derive(Clone))]
 #[derive(Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Timeline {
 	/// Timeline
 	tm: Vec<TimelineItem>
@@ -40,6 +42,7 @@ impl Timeline {
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 #[cfg_attr(target_arch = "wasm32", derive(Clone))]
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimelineItem {
 	pub cut: usize,
 	pub start: f64,
@@ -47,6 +50,26 @@ pub struct TimelineItem {
 }
 
 impl Timeline {
+	/// Visible parts of the cuts ordered by their start.
+	/// Disabled cuts aren't visible.
+	pub fn items(&self) -> &[TimelineItem] {
+		&self.tm
+	}
+
+	/// Ranges from `from` to the end of the last item not covered by any item.
+	pub fn gaps(&self, from: f64) -> Vec<(f64, f64)> {
+		let mut gaps = Vec::new();
+		let mut covered = from;
+		for item in self.tm.iter() {
+			if item.start > covered {
+				gaps.push((covered, item.start));
+			}
+			covered = covered.max(item.end);
+		}
+		gaps
+	}
+
+	/// Adds a cut on top of the ones added before.
 	pub(crate) fn add(&mut self, cut: usize, start: f64, end: f64) {
 		let tm_item = TimelineItem {
 			cut,
@@ -54,52 +77,33 @@ impl Timeline {
 			end,
 		};
 
-		if self.tm.is_empty() {
-			return self.tm.push(tm_item);
-		}
-
-		let mut left = 0;
-		let mut right = self.tm.len() - 1;
-		loop {
-			let middle = (right + left) / 2;
-			if self.tm[middle].start < start {
-				left = middle + 1;
-			} else if self.tm[middle].start > start {
-				right = if middle == 0 { 0 } else { middle - 1 };
-			} else {
-				break;
-			}
-
-			if left > right || (left == 0 && right == 0 && middle == 0) {
-				break;
-			}
-		}
-		self.tm.insert(left, tm_item);
 		self.fit(start, end);
+		let index = self.tm
+			.iter()
+			.position(|item| item.start > start)
+			.unwrap_or(self.tm.len());
+		self.tm.insert(index, tm_item);
 	}
 
+	/// Makes room for an item from `start` to `end` by trimming the items
+	/// it overlaps, splitting the ones it lies within and removing the ones it covers.
 	fn fit(&mut self, start: f64, end: f64) {
-		let mut i = 0;
-		while i != self.tm.len() {
-			let mut item = &mut self.tm[i];
-			i += 1;
-			if start > item.end {
-				// Starts after
+		let mut fitted: Vec<TimelineItem> = Vec::with_capacity(self.tm.len() + 1);
+		for item in self.tm.drain(..) {
+			if item.end <= start || item.start >= end {
+				// Doesn't overlap
+				fitted.push(item);
 				continue;
 			}
-			if start > item.start && start < item.end {
-				// Starts in the middle
-				item.end = start;
-			}
-			if end > item.end && end < item.end {
-				// Ends in the middle
-				item.start = end;
+			if item.start < start {
+				// Starts before
+				fitted.push(TimelineItem { cut: item.cut, start: item.start, end: start });
 			}
-			if start < item.start && end > item.end {
-				// Covers whole
-				self.tm.remove(i);
-				i -= 1;
+			if item.end > end {
+				// Ends after
+				fitted.push(TimelineItem { cut: item.cut, start: end, end: item.end });
 			}
-		};
+		}
+		self.tm = fitted;
 	}
 }
@@ -0,0 +1,56 @@
+#![cfg(feature = "async")]
+use prproj::errors::Error;
+use prproj::premiere::async_reader;
+use prproj::{PremiereFile, PremiereReader, Reader};
+use std::path::PathBuf;
+
+fn test_file(name: &str) -> PathBuf {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", name].iter().collect::<PathBuf>());
+    path
+}
+
+fn cut_ids(project: &PremiereFile) -> Vec<Vec<u32>> {
+    project.sequences
+        .iter()
+        .map(|seq| seq.cuts().iter().map(|cut| cut.id).collect())
+        .collect()
+}
+
+#[tokio::test]
+async fn it_reads_the_same_as_the_blocking_reader() {
+    let mut blocking = PremiereReader::from_path(&test_file("test.unzipped.prproj"));
+    blocking.read().unwrap();
+    let blocking = blocking.take();
+
+    for name in ["test.zipped.prproj", "test.unzipped.prproj"] {
+        let file = tokio::fs::File::open(test_file(name)).await.unwrap();
+        let project = async_reader::read(file).await.unwrap();
+        assert_eq!(cut_ids(&project), cut_ids(&blocking));
+        assert_eq!(project.media.len(), blocking.media.len());
+        assert_eq!(project.items.len(), blocking.items.len());
+    }
+}
+
+#[tokio::test]
+async fn it_reports_broken_projects() {
+    let zipped = std::fs::read(test_file("test.zipped.prproj")).unwrap();
+    let truncated = async_reader::read(&zipped[..zipped.len() / 2]).await;
+    assert!(matches!(truncated, Err(Error::Io(_))));
+
+    let mismatched = async_reader::read(&b"<PremiereData><Project></PremiereData>"[..]).await;
+    assert!(matches!(mismatched, Err(Error::AsyncXml(_))));
+}
+
+#[tokio::test]
+async fn it_rejects_namespaced_elements() {
+    for xml in [
+        &b"<PremiereData><pr:Project/></PremiereData>"[..],
+        &b"<PremiereData xmlns=\"urn:premiere\"><Project/></PremiereData>"[..],
+        &b"<PremiereData><Project xmlns:pr=\"urn:premiere\" pr:ObjectID=\"1\"/></PremiereData>"[..],
+    ] {
+        let project = async_reader::read(xml).await;
+        assert!(matches!(project, Err(Error::Xml(minidom::Error::InvalidElement))));
+    }
+}
@@ -0,0 +1,73 @@
+use prproj::decompress::{Callback, Decompress, Libflate};
+use prproj::errors::Error;
+use prproj::{PremiereReader, Reader};
+use std::cell::Cell;
+use std::io::Read;
+use std::path::PathBuf;
+
+fn test_file(name: &str) -> Vec<u8> {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", name].iter().collect::<PathBuf>());
+    std::fs::read(path).unwrap()
+}
+
+fn sequence_names(mut reader: PremiereReader) -> Vec<String> {
+    reader.read().unwrap();
+    reader.take().sequences.iter().map(|seq| seq.name().to_owned()).collect()
+}
+
+#[test]
+fn it_decompresses_with_a_callback() {
+    let calls = Cell::new(0);
+    let inflate = Callback(|gzip: &[u8]| {
+        calls.set(calls.get() + 1);
+        let mut xml = Vec::new();
+        Libflate.decompress(Box::new(gzip)).unwrap().read_to_end(&mut xml).unwrap();
+        Ok(xml)
+    });
+
+    let zipped = test_file("test.zipped.prproj");
+    let with_callback = PremiereReader::with_decompressor(&zipped, &inflate).unwrap();
+    assert_eq!(calls.get(), 1);
+    assert_eq!(sequence_names(with_callback), sequence_names(PremiereReader::new(&zipped)));
+
+    // Plain XML isn't decompressed
+    PremiereReader::with_decompressor(&test_file("test.unzipped.prproj"), &inflate).unwrap();
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn it_reports_broken_projects() {
+    let mismatched = PremiereReader::with_decompressor(b"<PremiereData><Project></PremiereData>", &Libflate);
+    assert!(matches!(mismatched, Err(Error::Xml(_))));
+
+    let zipped = test_file("test.zipped.prproj");
+    let truncated = PremiereReader::with_decompressor(&zipped[..zipped.len() / 2], &Libflate);
+    assert!(matches!(truncated, Err(Error::Io(_))));
+}
+
+/// Hands out one byte per `read`.
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.0.is_empty() || buf.is_empty() {
+            return Ok(0);
+        }
+        buf[0] = self.0[0];
+        self.0 = &self.0[1..];
+        Ok(1)
+    }
+}
+
+#[test]
+fn it_reads_from_streams() {
+    let zipped = test_file("test.zipped.prproj");
+    let unzipped = test_file("test.unzipped.prproj");
+    let expected = sequence_names(PremiereReader::new(&zipped));
+
+    assert_eq!(sequence_names(PremiereReader::from_reader(Trickle(&zipped)).unwrap()), expected);
+    assert_eq!(sequence_names(PremiereReader::from_reader(Trickle(&unzipped)).unwrap()), expected);
+    assert!(matches!(PremiereReader::from_reader(Trickle(&[])), Err(Error::Xml(_))));
+}
@@ -0,0 +1,160 @@
+use prproj::diff::{diff, Change};
+use prproj::premiere::ProjectItem;
+use prproj::{PremiereFile, PremiereReader, Reader};
+use std::path::PathBuf;
+
+const SECOND: u64 = 254_016_000_000;
+
+fn xml() -> String {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn read(xml: &str) -> PremiereFile {
+    let mut reader = PremiereReader::new(xml.as_bytes());
+    reader.read().unwrap();
+    reader.take()
+}
+
+#[test]
+fn it_finds_no_changes_between_equal_projects() {
+    let xml = xml();
+    assert_eq!(diff(&read(&xml), &read(&xml)), []);
+}
+
+#[test]
+fn it_reports_renames_relinks_and_moved_cuts() {
+    let old = xml();
+    // Clip 130 on V1 and its audio are pushed back by a second
+    let new = old
+        .replace(
+            "<Start>5715360000000</Start>\n\t\t\t\t<End>8752968000000</End>",
+            &format!("<Start>{}</Start>\n\t\t\t\t<End>{}</End>", 5715360000000 + SECOND, 8752968000000 + SECOND),
+        )
+        .replace("<Name>test_supercut</Name>", "<Name>final_cut</Name>")
+        .replace(
+            "<FilePath>D:\\temp-download\\Blender Open Movies\\Spring",
+            "<FilePath>E:\\footage\\Spring",
+        );
+    let changes = diff(&read(&old), &read(&new));
+    let lines: Vec<String> = changes.iter().map(|change| change.to_string()).collect();
+
+    assert!(matches!(&changes[0], Change::MediumRelinked { new_path, .. } if new_path.starts_with("E:\\footage\\Spring")));
+    assert!(lines.contains(&"~ item \"test_supercut\" renamed to \"final_cut\"".to_owned()));
+    assert!(lines.contains(&"~ sequence \"test_supercut\" renamed to \"final_cut\"".to_owned()));
+    assert!(lines.contains(&
+        "~ \"final_cut\" Spring - Blender Open Movie (858p_24fps_VP9-128kbit_Opus).webm moved \
+        V1 00:00:22:12-00:00:34:11 -> V1 00:00:23:12-00:00:35:11".to_owned()
+    ));
+    assert!(changes.iter().any(|change| matches!(change, Change::CutMoved { old, .. } if old.track == "A1")));
+    assert_eq!(changes.len(), 5, "{:#?}", lines);
+}
+
+#[test]
+fn it_reports_bins_added_moved_and_removed() {
+    let old = read(&xml());
+    let mut new = read(&xml());
+    new.items.push(ProjectItem {
+        uid: String::from("bin"),
+        name: String::from("Footage"),
+        bin: Vec::new(),
+        is_bin: true,
+    });
+    new.items[0].bin = vec![String::from("Footage")];
+
+    let changes = diff(&old, &new);
+    assert!(matches!(&changes[0], Change::ItemMoved { old_bin, new_bin, .. } if old_bin.is_empty() && new_bin == "Footage"));
+    assert!(matches!(&changes[1], Change::ItemAdded { name, .. } if name == "Footage"));
+
+    let removed = diff(&new, &old);
+    assert!(matches!(&removed[1], Change::ItemRemoved { name, .. } if name == "Footage"));
+}
+
+/// A `<DVAMarker>` as Premiere saves it in a `<Markers>` object.
+fn dva_marker(id: &str, name: &str, start: u64, color: u32) -> String {
+    format!(
+        "<DVAMarker>{{&quot;DVAMarker&quot;:{{&quot;mComment&quot;:&quot;&quot;,&quot;mDuration&quot;:{{&quot;ticks&quot;:&quot;0&quot;}},\
+        &quot;mMarkerID&quot;:&quot;{}&quot;,&quot;mName&quot;:&quot;{}&quot;,&quot;mStartTime&quot;:{{&quot;ticks&quot;:&quot;{}&quot;}},\
+        &quot;mColorIndex&quot;:{}}}}}</DVAMarker>",
+        id, name, start, color
+    )
+}
+
+/// Puts `clip_markers` on the Spring clips and `sequence_markers` on
+/// the clip of the master clip of `some_useless_sequence`.
+fn with_markers(xml: &str, clip_markers: &[String], sequence_markers: &[String]) -> String {
+    xml
+        .replace(
+            "<LastContentState>00000000-0000-0000-0000-000000000000</LastContentState>\n\t</Markers>\n\t<VideoMediaSource ObjectID=\"66\"",
+            &format!(
+                "<LastContentState>00000000-0000-0000-0000-000000000000</LastContentState>\n\t\t{}\n\t</Markers>\n\t<VideoMediaSource ObjectID=\"66\"",
+                clip_markers.concat()
+            ),
+        )
+        .replace(
+            "\t\t\t<Source ObjectRef=\"86\"/>",
+            "\t\t\t<MarkerOwner Version=\"1\">\n\t\t\t\t<Markers ObjectRef=\"9201\"/>\n\t\t\t</MarkerOwner>\n\t\t\t<Source ObjectRef=\"86\"/>",
+        )
+        .replace(
+            "</PremiereData>",
+            &format!(
+                "\t<Markers ObjectID=\"9201\" ClassID=\"bee50706-b524-416c-9f03-b596ce5f6866\" Version=\"3\">\n\t\t\
+                <ByGUID>byGUID</ByGUID>\n\t\t{}\n\t</Markers>\n</PremiereData>",
+                sequence_markers.concat()
+            ),
+        )
+}
+
+#[test]
+fn it_reads_sequence_and_clip_markers() {
+    let project = read(&with_markers(
+        &xml(),
+        &[dva_marker("a", "Explosion", 10 * SECOND, 3)],
+        &[dva_marker("b", "Act 2", 2 * SECOND, 0), dva_marker("c", "Intro", SECOND, 5)],
+    ));
+    let markers: Vec<(&str, f64, u32)> = project.sequences[0]
+        .markers()
+        .iter()
+        .map(|marker| (marker.name.as_str(), marker.start, marker.color))
+        .collect();
+    assert_eq!(markers, [("Intro", 1., 5), ("Act 2", 2., 0)]);
+    assert!(project.sequences[1].markers().is_empty());
+
+    let spring = project.sequences[1].cuts().iter().find(|cut| !cut.markers().is_empty()).unwrap();
+    assert_eq!(spring.markers()[0].name, "Explosion");
+    assert_eq!(spring.markers()[0].id, "a");
+    assert_eq!(spring.markers()[0].start, 10.);
+}
+
+#[test]
+fn it_reports_edited_markers() {
+    let xml = xml();
+    let old = with_markers(
+        &xml,
+        &[dva_marker("a", "Explosion", 420 * SECOND, 3)],
+        &[dva_marker("b", "Act 2", 2 * SECOND, 0)],
+    );
+    let new = with_markers(
+        &xml,
+        &[dva_marker("a", "Big explosion", 420 * SECOND, 1)],
+        &[dva_marker("c", "Intro", SECOND, 0)],
+    );
+    let changes = diff(&read(&old), &read(&new));
+    let lines: Vec<String> = changes.iter().map(|change| change.to_string()).collect();
+
+    assert_eq!(lines[0], "- \"some_useless_sequence\" marker \"Act 2\" at 00:00:02:00");
+    assert_eq!(lines[1], "+ \"some_useless_sequence\" marker \"Intro\" at 00:00:01:00");
+    // Only the Spring clips playing the marker show it on the timeline
+    assert!(matches!(
+        &changes[2],
+        Change::MarkerChanged { sequence, cut: Some(cut), old, new, .. }
+            if sequence == "test_supercut" && cut.track == "A1" && old.color == 3 && new.color == 1
+    ));
+    assert_eq!(
+        lines[3],
+        "~ \"test_supercut\" V1 00:00:22:12-00:00:34:11 marker \"Explosion\" changed at 00:00:27:01"
+    );
+    assert_eq!(changes.len(), 4, "{:#?}", lines);
+}
@@ -2,6 +2,7 @@
 extern crate lazy_static;
 
 use prproj::{PremiereReader, Reader};
+use prproj::premiere::{CutSource, TrackKind};
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
@@ -26,7 +27,7 @@ lazy_static! {
 
 fn read(path: &Path) {
     let before = Instant::now();
-    let mut reader = PremiereReader::from_path(&Path::new(path));
+    let mut reader = PremiereReader::from_path(path);
     if let Err(err) = reader.read() {
         println!("Error reading Premiere Pro file: {}\n{:#?}!", err, err)
     }
@@ -38,14 +39,285 @@ fn read(path: &Path) {
 #[test]
 fn it_reads_xml_without_errors() {
     println!("{:?}", &*XML_FILE);
-    read(&*XML_FILE);
+    read(&XML_FILE);
 }
 
 #[test]
 fn it_reads_gzip_without_errors() {
-    read(&*GZ_FILE);
+    read(&GZ_FILE);
 }
 
+const TICKS_PER_SECOND: u64 = 254_016_000_000;
 
+/// Puts "test_supercut" from 2s to 12s on V1 of the empty "some_useless_sequence",
+/// at 10s to 20s of its timeline.
+fn project_with_nested_sequence() -> String {
+    let xml = std::fs::read_to_string(&*XML_FILE).unwrap();
+    // V1 of "some_useless_sequence"
+    let at = xml.find("ObjectUID=\"add1a5be-827a-4665-af38-55b9a36c6338\"").unwrap();
+    let at = at + xml[at..].find("<ClipItems").unwrap();
+    let at = at + xml[at..].find("</MediaType>").unwrap() + "</MediaType>".len();
+    let end = xml.rfind("</PremiereData>").unwrap();
+    format!(
+        "{}<TrackItems Version=\"1\"><TrackItem Index=\"0\" ObjectRef=\"9001\"/></TrackItems>{}\
+        <VideoClipTrackItem ObjectID=\"9001\" ClassID=\"368b0406-29e3-4923-9fcd-094fbf9a1089\" Version=\"6\">\
+            <ClipTrackItem Version=\"8\">\
+                <ComponentOwner Version=\"1\"/>\
+                <TrackItem Version=\"3\"><Start>{}</Start><End>{}</End></TrackItem>\
+                <SubClip ObjectRef=\"9002\"/>\
+            </ClipTrackItem>\
+        </VideoClipTrackItem>\
+        <SubClip ObjectID=\"9002\" ClassID=\"e0c58dc9-dbdd-4166-aef7-5db7e3f22e84\" Version=\"5\">\
+            <Clip ObjectRef=\"9003\"/>\
+            <MasterClip ObjectURef=\"dfd47690-1a69-4f27-869e-a9796dc93dec\"/>\
+            <Name>test_supercut</Name>\
+        </SubClip>\
+        <VideoClip ObjectID=\"9003\" ClassID=\"9308dbef-2440-4acb-9ab2-953b9a4e82ec\" Version=\"11\">\
+            <Clip Version=\"18\">\
+                <Source ObjectRef=\"9004\"/>\
+                <InPoint>{}</InPoint>\
+                <OutPoint>{}</OutPoint>\
+            </Clip>\
+        </VideoClip>\
+        <VideoSequenceSource ObjectID=\"9004\" ClassID=\"3c3dd0c8-4bf6-4cbb-8fd6-7f4c0a0e36f1\" Version=\"1\">\
+            <SequenceSource Version=\"4\"><Sequence ObjectURef=\"dfd47690-1a69-4f27-869e-a9796dc93dec\"/></SequenceSource>\
+        </VideoSequenceSource>{}",
+        &xml[..at],
+        &xml[at..end],
+        10 * TICKS_PER_SECOND,
+        20 * TICKS_PER_SECOND,
+        2 * TICKS_PER_SECOND,
+        12 * TICKS_PER_SECOND,
+        &xml[end..],
+    )
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-6, "{} != {}", actual, expected);
+}
+
+#[test]
+fn it_reads_nested_sequences_as_cuts() {
+    let mut reader = PremiereReader::new(project_with_nested_sequence().as_bytes());
+    reader.read().unwrap();
+    let cuts: Vec<_> = reader.sequences()[0].cuts().iter().collect();
+    assert_eq!(cuts.len(), 1);
+    assert!(matches!(cuts[0].source(), CutSource::Sequence(2)));
+    assert_close(cuts[0].start, 10.);
+    assert_close(cuts[0].in_point, 2.);
+}
+
+#[test]
+fn it_flattens_nested_sequences_into_media_cuts() {
+    let mut reader = PremiereReader::new(project_with_nested_sequence().as_bytes());
+    reader.set_flatten_nested(true);
+    reader.read().unwrap();
+    let mut cuts: Vec<_> = reader.sequences()[0].cuts().iter().collect();
+    cuts.sort_by(|a, b| a.start.partial_cmp(&b.start).unwrap());
+    // V2 from 0s and V1 from 4s of "test_supercut" fall into the used 2s to 12s,
+    // with IDs following the one of the nested clip
+    assert_eq!(cuts.iter().map(|cut| cut.id).collect::<Vec<_>>(), [9003, 9002]);
+    assert_eq!((cuts[0].kind, cuts[0].track), (TrackKind::Video, 1));
+    assert_eq!((cuts[1].kind, cuts[1].track), (TrackKind::Video, 0));
+    for cut in cuts.iter() {
+        match cut.source() {
+            CutSource::Medium(id) => assert!(reader.media().get(*id).is_some()),
+            CutSource::Sequence(_) => panic!("{} is still nested", cut.id),
+        }
+    }
+    // Starts 2s into its clip at 17.5s, ends with it
+    assert_close(cuts[0].start, 10.);
+    assert_close(cuts[0].end, 10. + 1598184000000. / TICKS_PER_SECOND as f64 - 2.);
+    assert_close(cuts[0].in_point, 19.5);
+    assert_close(cuts[0].out_point, 17.5 + 1598184000000. / TICKS_PER_SECOND as f64);
+    // Starts with its clip at 4s, cut off at 12s
+    assert_close(cuts[1].start, 12.);
+    assert_close(cuts[1].end, 20.);
+    assert_close(cuts[1].in_point, 5.625);
+    assert_close(cuts[1].out_point, 5.625 + 8.);
+
+    // The nested sequence itself keeps its cuts
+    let supercut = &reader.sequences()[1];
+    assert_eq!(supercut.cuts().iter().count(), 18);
+    assert!(supercut.cuts().iter().all(|cut| matches!(cut.source(), CutSource::Medium(_))));
+}
+
+#[test]
+fn it_gives_sequences_nested_twice_their_own_cut_ids() {
+    // The same part of "test_supercut" once more at 30s to 40s
+    let xml = project_with_nested_sequence()
+        .replace(
+            "<TrackItem Index=\"0\" ObjectRef=\"9001\"/>",
+            "<TrackItem Index=\"0\" ObjectRef=\"9001\"/><TrackItem Index=\"1\" ObjectRef=\"9005\"/>",
+        )
+        .replace(
+            "</PremiereData>",
+            &format!(
+                "<VideoClipTrackItem ObjectID=\"9005\" ClassID=\"368b0406-29e3-4923-9fcd-094fbf9a1089\" Version=\"6\">\
+                    <ClipTrackItem Version=\"8\">\
+                        <ComponentOwner Version=\"1\"/>\
+                        <TrackItem Version=\"3\"><Start>{}</Start><End>{}</End></TrackItem>\
+                        <SubClip ObjectRef=\"9002\"/>\
+                    </ClipTrackItem>\
+                </VideoClipTrackItem></PremiereData>",
+                30 * TICKS_PER_SECOND,
+                40 * TICKS_PER_SECOND,
+            ),
+        );
+    let mut reader = PremiereReader::new(xml.as_bytes());
+    reader.set_flatten_nested(true);
+    reader.read().unwrap();
+    let seq = &reader.sequences()[0];
+    let mut ids: Vec<u32> = seq.cuts().iter().map(|cut| cut.id).collect();
+    assert_eq!(ids.len(), 4);
+    ids.sort_unstable();
+    ids.dedup();
+    assert_eq!(ids.len(), 4);
+    for cut in seq.cuts().iter() {
+        assert!(seq.linked_cuts(cut).all(|linked| linked.id != cut.id));
+    }
+}
+
+#[test]
+fn it_fails_on_invalid_sequence_ids() {
+    use prproj::errors::Error;
+
+    let xml = std::fs::read_to_string(&*XML_FILE)
+        .unwrap()
+        .replace("<ID>2</ID>\n\t\t<Name>test_supercut", "<ID>two</ID>\n\t\t<Name>test_supercut");
+    let mut reader = PremiereReader::new(xml.as_bytes());
+    assert!(matches!(reader.read(), Err(Error::Invalid(_))));
+}
+
+#[test]
+fn it_reads_intrinsic_effects_of_clips() {
+    let mut reader = PremiereReader::from_path(&XML_FILE);
+    reader.read().unwrap();
+    let seq = &reader.sequences()[1];
+    assert!(seq.cuts().iter().any(|cut| cut.kind == TrackKind::Audio));
+    for cut in seq.cuts().iter() {
+        let expected = match cut.kind {
+            TrackKind::Video => ["Motion", "Opacity"],
+            TrackKind::Audio => ["Volume", "Channel Volume"],
+        };
+        for name in expected.iter() {
+            assert!(cut.has_effect(name), "{} missing in {:?}", name, cut.effects());
+        }
+        assert!(cut.effects().iter().all(|effect| effect.intrinsic));
+    }
+}
+
+#[test]
+fn it_links_audio_and_video_of_clips() {
+    let mut reader = PremiereReader::from_path(&XML_FILE);
+    reader.read().unwrap();
+    let seq = &reader.sequences()[1];
+    let video = seq.cuts().iter().find(|cut| cut.id == 124).unwrap();
+    assert_eq!(video.kind, TrackKind::Video);
+    assert_eq!(video.linked(), &[123]);
+
+    let audio: Vec<_> = seq.linked_cuts(video).collect();
+    assert_eq!(audio.len(), 1);
+    assert_eq!(audio[0].kind, TrackKind::Audio);
+    assert_eq!(audio[0].linked(), &[124]);
+    assert!(seq.cuts().iter().all(|cut| cut.is_linked()));
+}
+
+/// Groups the first two video clips on V1 of "test_supercut" with the one on V2.
+fn project_with_group() -> String {
+    let xml = std::fs::read_to_string(&*XML_FILE).unwrap();
+    let at = xml.find("<Sequence ObjectUID=\"dfd47690-1a69-4f27-869e-a9796dc93dec\"").unwrap();
+    let at = at + xml[at..].find("</LinkContainer>").unwrap() + "</LinkContainer>".len();
+    let end = xml.rfind("</PremiereData>").unwrap();
+    format!(
+        "{}<GroupContainer Version=\"1\"><Groups Version=\"1\"><Group Index=\"0\" ObjectRef=\"9101\"/></Groups></GroupContainer>{}\
+        <Group ObjectID=\"9101\" ClassID=\"149d4ea5-a7d4-4b34-9bb7-16d783904bf2\" Version=\"1\">\
+            <TrackItemGroup Version=\"1\"><TrackItems Version=\"1\">\
+                <TrackItem Index=\"0\" ObjectRef=\"124\"/>\
+                <TrackItem Index=\"1\" ObjectRef=\"126\"/>\
+                <TrackItem Index=\"2\" ObjectRef=\"128\"/>\
+            </TrackItems></TrackItemGroup>\
+        </Group>{}",
+        &xml[..at],
+        &xml[at..end],
+        &xml[end..],
+    )
+}
+
+#[test]
+fn it_groups_clips_apart_from_links() {
+    let mut reader = PremiereReader::new(project_with_group().as_bytes());
+    reader.read().unwrap();
+    let seq = &reader.sequences()[1];
+    let video = seq.cuts().iter().find(|cut| cut.id == 126).unwrap();
+    assert_eq!(video.grouped(), &[124, 128]);
+    // Its audio is linked but not part of the group
+    assert_eq!(video.linked(), &[125]);
+    let grouped: Vec<_> = seq.grouped_cuts(video).map(|cut| cut.id).collect();
+    assert_eq!(grouped, [128, 124]);
+
+    let grouped_ids: Vec<_> = seq.cuts().iter().filter(|cut| cut.is_grouped()).map(|cut| cut.id).collect();
+    assert_eq!(grouped_ids, [126, 128, 124]);
+    assert!(seq.cuts().iter().all(|cut| cut.is_linked()));
+}
+
+#[test]
+fn it_has_no_groups_without_a_group_container() {
+    let mut reader = PremiereReader::from_path(&XML_FILE);
+    reader.read().unwrap();
+    assert!(reader.sequences()[1].cuts().iter().all(|cut| !cut.is_grouped()));
+}
 
+#[test]
+fn it_reads_sequence_settings() {
+    use prproj::premiere::{ChannelType, FieldOrder};
+
+    let mut reader = PremiereReader::from_path(&XML_FILE);
+    reader.read().unwrap();
+    let seq = &reader.sequences()[1];
+    let settings = seq.settings();
+    assert_eq!(settings.fps(), 24.);
+    assert_eq!((settings.frame_size.width, settings.frame_size.height), (2048, 858));
+    assert_eq!(settings.pixel_aspect_ratio, (1, 1));
+    assert_eq!(settings.field_order, FieldOrder::Progressive);
+    assert_eq!(settings.audio_sample_rate, 48000);
+    assert_eq!(settings.audio_channels, ChannelType::Stereo);
+
+    let preview = &settings.preview;
+    assert_eq!(preview.format, "205e8745-4e05-a1ba-408c-47be000000fa");
+    assert_eq!(preview.file_type.to_string(), "MPGI");
+    assert_eq!(preview.codec.to_string(), "MPIN");
+    assert!(preview.preset_path.ends_with("I-Frame Only MPEG.epr"));
+    assert_eq!(preview.frame_size.width, 1920);
+    assert!(!preview.max_bit_depth && !preview.max_render_quality);
+}
+
+#[test]
+fn it_refers_to_each_medium_once() {
+    use prproj::premiere::CutSource;
 
+    let mut reader = PremiereReader::from_path(&XML_FILE);
+    reader.read().unwrap();
+    let project = reader.take();
+    assert_eq!(project.media.len(), 3);
+    let seq = &project.sequences[1];
+    for cut in seq.cuts().iter() {
+        match cut.source() {
+            CutSource::Medium(id) => assert!(project.medium(*id).is_some()),
+            CutSource::Sequence(_) => panic!("no nested sequences expected"),
+        }
+    }
+}
+
+#[test]
+fn it_reads_projects_on_other_threads() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<prproj::PremiereFile>();
+
+    let project = std::thread::spawn(|| {
+        let mut reader = PremiereReader::from_path(&GZ_FILE);
+        reader.read().unwrap();
+        reader.take()
+    }).join().unwrap();
+    assert_eq!(project.sequences.len(), 2);
+}
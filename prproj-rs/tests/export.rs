@@ -0,0 +1,124 @@
+use prproj::export::{csv, edl, fcp7};
+use prproj::{PremiereFile, PremiereReader, Reader};
+use std::path::PathBuf;
+
+fn project() -> PremiereFile {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    let mut reader = PremiereReader::from_path(&path);
+    reader.read().unwrap();
+    reader.take()
+}
+
+#[test]
+fn it_writes_cmx3600_edl() {
+    let project = project();
+    let seq = &project.sequences[1];
+    let edl = edl::write(&project, seq);
+    assert!(edl.starts_with("TITLE: test_supercut\nFCM: NON-DROP FRAME\n"));
+
+    let events: Vec<_> = edl.lines().filter(|line| line.starts_with("0")).collect();
+    let video = events
+        .iter()
+        .filter(|line| line.split_whitespace().nth(2) == Some("V"))
+        .count();
+    assert_eq!(video, seq.timeline().items().len());
+    // The clip on V2 covers the start of V1
+    assert_eq!(
+        events[0],
+        "001  AX       V     C        00:00:17:12 00:00:23:19 00:00:00:00 00:00:06:07"
+    );
+    assert!(edl.contains("* FROM CLIP NAME: Spring - Blender Open Movie"));
+}
+
+#[test]
+fn it_writes_fcp7_xml_with_shared_files() {
+    let project = project();
+    let seq = &project.sequences[1];
+    let xml = fcp7::write(&project, seq);
+    assert!(xml.contains("<xmeml version=\"4\">"));
+    assert!(xml.contains("<name>test_supercut</name>"));
+    assert!(xml.contains("<width>2048</width>"));
+    assert_eq!(xml.matches("<clipitem ").count(), seq.cuts().len());
+    // Every file is described once and referenced afterwards
+    assert_eq!(xml.matches("<pathurl>").count(), 3);
+    assert!(xml.contains("<linkclipref>clipitem-125</linkclipref>"));
+    assert_eq!(xml.matches("<enabled>TRUE</enabled>").count(), seq.cuts().len());
+}
+
+#[test]
+fn it_writes_disabled_clips_to_fcp7_xml() {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    // Disables the first clip, the audio of clip 124
+    let xml = std::fs::read_to_string(path).unwrap().replacen(
+        "<ClipTrackItem Version=\"8\">",
+        "<ClipTrackItem Version=\"8\">\n\t\t\t<Disabled>true</Disabled>",
+        1,
+    );
+    let mut reader = PremiereReader::new(xml.as_bytes());
+    reader.read().unwrap();
+    let project = reader.take();
+    let xml = fcp7::write(&project, &project.sequences[1]);
+    let clip = &xml[xml.find("<clipitem id=\"clipitem-123\">").unwrap()..];
+    let clip = &clip[..clip.find("</clipitem>").unwrap()];
+    assert!(clip.contains("<enabled>FALSE</enabled>"));
+    assert_eq!(xml.matches("<enabled>FALSE</enabled>").count(), 1);
+}
+
+#[test]
+fn it_writes_a_row_per_clip() {
+    let project = project();
+    let seq = &project.sequences[1];
+    let csv = csv::write(&project, seq);
+    let rows: Vec<_> = csv.lines().collect();
+    assert_eq!(rows[0], "id,track,start,end,start_timecode,end_timecode,in_point,out_point,speed,reverse,name,path");
+    assert_eq!(rows.len(), seq.cuts().len() + 1);
+    assert!(rows[1..].iter().all(|row| row.ends_with(".webm")));
+}
+
+#[test]
+fn it_writes_otio_json() {
+    use prproj::export::otio;
+
+    let project = project();
+    let timeline: serde_json::Value = serde_json::from_str(&otio::write(&project, &project.sequences[1])).unwrap();
+    assert_eq!(timeline["OTIO_SCHEMA"], "Timeline.1");
+    assert_eq!(timeline["name"], "test_supercut");
+    let tracks: Vec<_> = timeline["tracks"]["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|track| (track["name"].as_str().unwrap(), track["children"].as_array().unwrap().len()))
+        .collect();
+    // V1 starts with a gap
+    assert_eq!(tracks, [("V1", 9), ("V2", 1), ("A1", 9), ("A2", 1)]);
+
+    let clip = &timeline["tracks"]["children"][1]["children"][0];
+    assert_eq!(clip["name"], "Spring - Blender Open Movie (858p_24fps_VP9-128kbit_Opus).webm");
+    assert_eq!(clip["source_range"]["start_time"], serde_json::json!({
+        "OTIO_SCHEMA": "RationalTime.1",
+        "rate": 24.,
+        "value": 420.,
+    }));
+    assert!(clip["media_reference"]["target_url"].as_str().unwrap().starts_with("file://localhost/D%3a/temp-download/"));
+}
+
+#[test]
+fn it_writes_sequences_as_json() {
+    use prproj::export::json;
+
+    let project = project();
+    let seq = &project.sequences[1];
+    let written: serde_json::Value = serde_json::from_str(&json::write(&project, seq)).unwrap();
+    assert_eq!(written, json::sequence(&project, seq));
+    assert_eq!(written["name"], "test_supercut");
+    assert_eq!(written["clips"].as_array().unwrap().len(), 18);
+
+    let clip = json::cut(&project, seq.cuts().iter().find(|cut| cut.id == 124).unwrap());
+    assert_eq!(clip["track"], "V2");
+    assert_eq!(clip["in_point"], 17.5);
+    assert_eq!(clip["linked"], serde_json::json!([123]));
+}
@@ -0,0 +1,133 @@
+use prproj::export::ffmpeg::{concat_list, FilterGraph, SpeedChange};
+use prproj::{PremiereFile, PremiereReader, Reader};
+use std::path::PathBuf;
+
+fn xml() -> String {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn read(xml: &str) -> PremiereFile {
+    let mut reader = PremiereReader::new(xml.as_bytes());
+    reader.read().unwrap();
+    reader.take()
+}
+
+fn project() -> PremiereFile {
+    read(&xml())
+}
+
+/// Makes the video clip on V2 of "test_supercut" play in reverse at 50%.
+fn project_with_speed_change() -> PremiereFile {
+    let xml = xml();
+    let at = xml.find("<VideoClip ObjectID=\"208\"").unwrap();
+    let at = at + xml[at..].find("<InPoint>").unwrap();
+    read(&format!("{}<PlaybackSpeed>-0.5</PlaybackSpeed>{}", &xml[..at], &xml[at..]))
+}
+
+const SPRING: &str = "D:\\temp-download\\Blender Open Movies\\Spring - Blender Open Movie (858p_24fps_VP9-128kbit_Opus).webm";
+const AGENT: &str = "D:\\temp-download\\Blender Open Movies\\Agent 327_ Operation Barbershop (858p_24fps_VP9-128kbit_Opus).webm";
+const COSMOS: &str = "D:\\temp-download\\Blender Open Movies\\Cosmos Laundromat - First Cycle. Official Blender Foundation release. (858p_24fps_VP9-128kbit_Opus).webm";
+
+#[test]
+fn it_writes_concat_list_of_visible_segments() {
+    let project = project();
+    let list = concat_list(&project, &project.sequences[1]).unwrap();
+    let expected = [
+        // V2 covers the start of the first clip on V1
+        (SPRING, "17.5", "23.791667"),
+        (COSMOS, "7.916667", "16.875"),
+        (AGENT, "2.958333", "10.208333"),
+        (SPRING, "415.458333", "427.416667"),
+        (COSMOS, "50.083333", "59.625"),
+        (AGENT, "205.666667", "208.833333"),
+        (COSMOS, "718.583333", "730.583333"),
+        (AGENT, "218.125", "224.708333"),
+        (SPRING, "453.583333", "463.125"),
+    ];
+    let mut lines = list.lines();
+    assert_eq!(lines.next(), Some("ffconcat version 1.0"));
+    for (path, inpoint, outpoint) in expected.iter() {
+        assert_eq!(lines.next().unwrap(), format!("file '{}'", path));
+        assert_eq!(lines.next().unwrap(), format!("inpoint {}", inpoint));
+        assert_eq!(lines.next().unwrap(), format!("outpoint {}", outpoint));
+    }
+    assert_eq!(lines.next(), None);
+}
+
+#[test]
+fn it_refuses_speed_changes_in_concat_lists() {
+    let project = project_with_speed_change();
+    let err = concat_list(&project, &project.sequences[1]).unwrap_err();
+    assert_eq!(err, SpeedChange { cut: 124, start: 0., rate: 0.5, reverse: true });
+    assert_eq!(err.to_string(), "clip 124 at 0s plays at 50% in reverse, which a concat list can't render");
+}
+
+#[test]
+fn it_renders_speed_changes_in_filter_graphs() {
+    let project = project_with_speed_change();
+    let graph = FilterGraph::new(&project, &project.sequences[1]);
+    let spring = graph.inputs.iter().position(|input| input == SPRING).unwrap();
+    // 6.291667s on the timeline at 50% play 3.145833s of the source,
+    // ending where the clip's source range starts as it's reversed
+    let chain = graph.filter.lines().next().unwrap();
+    assert_eq!(
+        chain,
+        format!(
+            "[{}:v]trim=start=17.5:end=20.645833,setpts=PTS-STARTPTS,reverse,setpts=PTS/0.5,\
+            scale=2048:858:force_original_aspect_ratio=decrease,pad=2048:858:(ow-iw)/2:(oh-ih)/2,setsar=1,fps=24[v0];",
+            spring
+        )
+    );
+}
+
+#[test]
+fn it_builds_filter_graph_with_scaling_and_gaps() {
+    let project = project();
+    let seq = &project.sequences[1];
+    let graph = FilterGraph::new(&project, seq);
+    assert!(graph.has_video && graph.has_audio);
+    assert!(!graph.inputs.is_empty());
+    assert!(graph.filter.contains("scale=2048:858"));
+    assert!(graph.filter.contains("fps=24"));
+    assert!(graph.filter.contains("[v]"));
+    assert!(graph.filter.contains("[a]"));
+    // A2 is silent after its only clip
+    assert!(graph.filter.contains("anullsrc=r=48000:cl=stereo"));
+
+    let args = graph.args("out.mp4");
+    assert_eq!(args.last().unwrap(), "out.mp4");
+    assert_eq!(args.iter().filter(|arg| *arg == "-i").count(), graph.inputs.len());
+}
+
+#[test]
+fn it_leaves_empty_sequences_empty() {
+    let project = project();
+    let seq = &project.sequences[0];
+    assert_eq!(concat_list(&project, seq).unwrap(), "ffconcat version 1.0\n");
+    let graph = FilterGraph::new(&project, seq);
+    assert!(!graph.has_video && !graph.has_audio);
+}
+
+#[test]
+fn it_leaves_out_disabled_clips() {
+    let project = read(&xml().replace(
+        "<ClipTrackItem Version=\"8\">",
+        "<ClipTrackItem Version=\"8\">\n\t\t\t<Disabled>true</Disabled>",
+    ));
+    let seq = &project.sequences[1];
+    assert_eq!(concat_list(&project, seq).unwrap(), "ffconcat version 1.0\n");
+    let graph = FilterGraph::new(&project, seq);
+    assert!(!graph.has_video && !graph.has_audio);
+}
+
+#[test]
+fn it_leaves_out_muted_tracks() {
+    let project = read(&xml().replace("<IsMuted>false</IsMuted>", "<IsMuted>true</IsMuted>"));
+    let seq = &project.sequences[1];
+    assert_eq!(concat_list(&project, seq).unwrap(), "ffconcat version 1.0\n");
+    let graph = FilterGraph::new(&project, seq);
+    assert!(!graph.has_video && !graph.has_audio);
+}
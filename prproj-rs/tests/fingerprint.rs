@@ -0,0 +1,46 @@
+use prproj::fingerprint::{fingerprint, Fingerprint};
+use prproj::{PremiereReader, Reader};
+use std::path::PathBuf;
+
+const SECOND: u64 = 254_016_000_000;
+
+fn xml() -> String {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn fingerprints(xml: &str) -> Vec<Fingerprint> {
+    let mut reader = PremiereReader::new(xml.as_bytes());
+    reader.read().unwrap();
+    let project = reader.take();
+    project.sequences.iter().map(|seq| fingerprint(&project, seq)).collect()
+}
+
+#[test]
+fn it_ignores_names_and_ui_state() {
+    let xml = xml();
+    let original = fingerprints(&xml);
+    assert_ne!(original[0], original[1]);
+    assert_eq!(original, fingerprints(&xml));
+
+    let renamed = xml
+        .replace("<Name>test_supercut</Name>", "<Name>final_cut</Name>")
+        .replace("<TL.SQTrackExpandedHeight>25</TL.SQTrackExpandedHeight>", "<TL.SQTrackExpandedHeight>80</TL.SQTrackExpandedHeight>");
+    assert_eq!(original, fingerprints(&renamed));
+}
+
+#[test]
+fn it_changes_with_the_edit() {
+    let xml = xml();
+    let original = fingerprints(&xml);
+    // Clip 130 on V1 and its audio are pushed back by a second
+    let moved = xml.replace(
+        "<Start>5715360000000</Start>\n\t\t\t\t<End>8752968000000</End>",
+        &format!("<Start>{}</Start>\n\t\t\t\t<End>{}</End>", 5715360000000 + SECOND, 8752968000000 + SECOND),
+    );
+    let changed = fingerprints(&moved);
+    assert_eq!(original[0], changed[0]);
+    assert_ne!(original[1], changed[1]);
+}
@@ -0,0 +1,61 @@
+use prproj::gaps::{flash_frames, gaps};
+use prproj::{PremiereFile, PremiereReader, Reader};
+use std::path::PathBuf;
+
+const FRAME: u64 = 10_584_000_000;
+
+fn xml() -> String {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn read(xml: &str) -> PremiereFile {
+    let mut reader = PremiereReader::new(xml.as_bytes());
+    reader.read().unwrap();
+    reader.take()
+}
+
+#[test]
+fn it_finds_nothing_in_a_clean_sequence() {
+    let project = read(&xml());
+    for seq in project.sequences.iter() {
+        // V2 covers the start of V1
+        assert_eq!(gaps(seq), []);
+        assert_eq!(flash_frames(seq, 2), []);
+    }
+    assert_eq!(flash_frames(&project.sequences[1], 1000).len(), 18);
+}
+
+#[test]
+fn it_finds_flash_frames_on_every_track() {
+    // Clip 130 on V1 and its audio are shortened to one frame
+    let xml = xml().replace(
+        "<Start>5715360000000</Start>\n\t\t\t\t<End>8752968000000</End>",
+        &format!("<Start>5715360000000</Start>\n\t\t\t\t<End>{}</End>", 5715360000000 + FRAME),
+    );
+    let project = read(&xml);
+    let seq = &project.sequences[1];
+
+    let flash_frames = flash_frames(seq, 2);
+    let tracks: Vec<_> = flash_frames.iter().map(|flash_frame| flash_frame.position.track.as_str()).collect();
+    assert_eq!(tracks, ["V1", "A1"]);
+    assert!(flash_frames.iter().all(|flash_frame| flash_frame.frames == 1));
+
+    let gaps = gaps(seq);
+    assert_eq!(gaps.len(), 1);
+    assert_eq!(gaps[0].start_timecode.to_string(), "00:00:22:13");
+    assert_eq!(gaps[0].end_timecode.to_string(), "00:00:34:11");
+    assert_eq!(gaps[0].frames, 286);
+}
+
+#[test]
+fn it_finds_nothing_without_a_frame_rate() {
+    let xml = xml().replace("<FrameRate>10584000000</FrameRate>", "<FrameRate>0</FrameRate>");
+    let project = read(&xml);
+    let seq = &project.sequences[1];
+    assert_eq!(seq.settings().fps(), 0.);
+    assert_eq!(gaps(seq), []);
+    assert_eq!(flash_frames(seq, 1000), []);
+}
@@ -0,0 +1,80 @@
+use prproj::history::History;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const SECOND: u64 = 254_016_000_000;
+
+fn xml() -> String {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn write(path: &Path, xml: &str, minutes_ago: u64) {
+    std::fs::write(path, xml).unwrap();
+    let modified = SystemTime::now() - Duration::from_secs(minutes_ago * 60);
+    File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+}
+
+/// A directory of its own for every run of the tests, emptied first.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn it_finds_when_a_sequence_last_changed() {
+    let dir = temp_dir("prproj-history");
+    let auto_save = dir.join("Adobe Premiere Pro Auto-Save");
+    std::fs::create_dir_all(&auto_save).unwrap();
+
+    let old = xml();
+    // Clip 130 on V1 and its audio are pushed back by a second
+    let new = old.replace(
+        "<Start>5715360000000</Start>\n\t\t\t\t<End>8752968000000</End>",
+        &format!("<Start>{}</Start>\n\t\t\t\t<End>{}</End>", 5715360000000 + SECOND, 8752968000000 + SECOND),
+    );
+    write(&auto_save.join("test-2024-03-05_14-00-00.prproj"), &old, 30);
+    write(&auto_save.join("test-2024-03-05_14-15-00.prproj"), &old, 15);
+    write(&auto_save.join("test-2024-03-05_14-20-00.prproj"), &new, 10);
+    write(&auto_save.join("other-2024-03-05_14-25-00.prproj"), &old, 5);
+    write(&dir.join("test.prproj"), &new, 0);
+
+    let history = History::new(&dir.join("test.prproj"), &auto_save).unwrap();
+    let names: Vec<_> = history.versions()
+        .iter()
+        .map(|version| version.path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, [
+        "test-2024-03-05_14-00-00.prproj",
+        "test-2024-03-05_14-15-00.prproj",
+        "test-2024-03-05_14-20-00.prproj",
+        "test.prproj",
+    ]);
+    assert!(!history.latest().auto_save);
+
+    let changed = history.last_changed("test_supercut").unwrap().unwrap();
+    assert!(changed.path.ends_with("test-2024-03-05_14-20-00.prproj"));
+    let unchanged = history.last_changed("some_useless_sequence").unwrap().unwrap();
+    assert!(unchanged.path.ends_with("test-2024-03-05_14-00-00.prproj"));
+    assert!(history.last_changed("no such sequence").unwrap().is_none());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn it_lists_projects_which_were_never_auto_saved() {
+    let dir = temp_dir("prproj-history-never-saved");
+    std::fs::create_dir_all(&dir).unwrap();
+    write(&dir.join("test.prproj"), &xml(), 0);
+
+    let history = History::new(&dir.join("test.prproj"), &dir.join("Adobe Premiere Pro Auto-Save")).unwrap();
+    assert_eq!(history.versions().len(), 1);
+    let changed = history.last_changed("test_supercut").unwrap().unwrap();
+    assert!(!changed.auto_save);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,59 @@
+use prproj::premiere::keyframe::{decode, evaluate};
+use prproj::premiere::{Interpolation, ParamValue};
+
+const SECOND: i64 = 254_016_000_000;
+
+#[test]
+fn it_decodes_keyframes() {
+    let text = format!(
+        "{},100.,0,0,0,0,0,0;{},50.,4,0,0,0,0,0;{},0.5:0.25,5,0,0,0,0,0;",
+        0, SECOND, 2 * SECOND
+    );
+    let keyframes = decode(&text);
+    assert_eq!(keyframes.len(), 3);
+    assert_eq!(keyframes[0].value, ParamValue::Number(100.));
+    assert_eq!(keyframes[1].time, 1.);
+    assert_eq!(keyframes[1].interpolation, Interpolation::Other(4));
+    assert_eq!(keyframes[2].value, ParamValue::Point(0.5, 0.25));
+    assert_eq!(keyframes[2].interpolation, Interpolation::Other(5));
+}
+
+#[test]
+fn it_evaluates_keyframes() {
+    let text = format!(
+        "{},100.,0,0,0,0,0,0;{},50.,4,0,0,0,0,0;{},0.,0,0,0,0,0,0",
+        0, 2 * SECOND, 4 * SECOND
+    );
+    let keyframes = decode(&text);
+    assert_eq!(evaluate(&keyframes, -1.), Some(ParamValue::Number(100.)));
+    assert_eq!(evaluate(&keyframes, 1.), Some(ParamValue::Number(75.)));
+    // Unknown interpolations are evaluated linearly
+    assert_eq!(evaluate(&keyframes, 3.), Some(ParamValue::Number(25.)));
+    assert_eq!(evaluate(&keyframes, 10.), Some(ParamValue::Number(0.)));
+    assert_eq!(evaluate(&[], 1.), None);
+}
+
+/// `StartKeyframe`s of the Balance of the audio tracks in the test project,
+/// the only keyframes it has.
+#[test]
+fn it_decodes_keyframes_saved_by_premiere() {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<std::path::PathBuf>());
+    let xml = std::fs::read_to_string(path).unwrap();
+    let start_keyframes: Vec<&str> = xml
+        .split("<StartKeyframe>")
+        .skip(1)
+        .map(|rest| &rest[..rest.find("</StartKeyframe>").unwrap()])
+        .collect();
+    assert_eq!(start_keyframes.len(), 6);
+
+    for text in start_keyframes {
+        let keyframes = decode(text);
+        assert_eq!(keyframes.len(), 1);
+        assert_eq!(keyframes[0].time, -91_445_760_000_000_000. / SECOND as f64);
+        assert_eq!(keyframes[0].value, ParamValue::Number(0.5));
+        assert_eq!(keyframes[0].interpolation, Interpolation::Linear);
+        assert_eq!(evaluate(&keyframes, 0.), Some(ParamValue::Number(0.5)));
+    }
+}
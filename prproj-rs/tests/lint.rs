@@ -0,0 +1,61 @@
+use prproj::lint::{lint, Issue, LintConfig, Rule, Severity};
+use prproj::{PremiereReader, Reader};
+use std::path::PathBuf;
+
+const FRAME: u64 = 10_584_000_000;
+
+fn xml() -> String {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn issues(xml: &str, config: &LintConfig) -> Vec<Issue> {
+    let mut reader = PremiereReader::new(xml.as_bytes());
+    reader.read().unwrap();
+    lint(&reader.take(), config)
+}
+
+#[test]
+fn it_ignores_gaps_on_v1_filled_by_higher_tracks() {
+    // V1 of "test_supercut" starts at 4s, the clip on V2 fills the first 6s
+    let config = LintConfig {
+        rules: Rule::ALL.iter().copied().filter(|rule| *rule != Rule::OfflineMedia).collect(),
+        ..LintConfig::default()
+    };
+    assert_eq!(issues(&xml(), &config), []);
+}
+
+#[test]
+fn it_checks_every_rule() {
+    // Clip 130 on V1 and its audio are shortened to one frame
+    let xml = xml()
+        .replace(
+            "<Start>5715360000000</Start>\n\t\t\t\t<End>8752968000000</End>",
+            &format!("<Start>5715360000000</Start>\n\t\t\t\t<End>{}</End>", 5715360000000 + FRAME),
+        )
+        .replacen("<ClipTrackItem Version=\"8\">", "<ClipTrackItem Version=\"8\">\n\t\t\t<Disabled>true</Disabled>", 1)
+        .replacen("<IsMuted>false</IsMuted>", "<IsMuted>true</IsMuted>", 1)
+        .replace("<Name>some_useless_sequence</Name>", "<Name>test_supercut</Name>");
+    let config = LintConfig {
+        final_sequences: "SUPERCUT".to_owned(),
+        ..LintConfig::default()
+    };
+    let issues = issues(&xml, &config);
+    for rule in Rule::ALL.iter().filter(|rule| **rule != Rule::FrameRateMismatch) {
+        assert!(issues.iter().any(|issue| issue.rule == *rule), "no {}", rule);
+    }
+    let lines: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+    assert!(lines.contains(&"error[flash_frame] \"test_supercut\" 00:00:22:12-00:00:22:13: clip of 1 frames on V1".to_owned()));
+    assert!(lines.contains(&"error[gap] \"test_supercut\" 00:00:22:13-00:00:34:11: gap of 286 frames without video".to_owned()), "{:#?}", lines);
+    assert_eq!(issues.iter().find(|issue| issue.rule == Rule::Gap).unwrap().severity, Severity::Error);
+    assert!(lines.contains(&"warning[duplicate_sequence_name]: 2 sequences are named \"test_supercut\"".to_owned()));
+
+    let config = LintConfig {
+        rules: vec![Rule::FlashFrame],
+        flash_frames: 0,
+        ..LintConfig::default()
+    };
+    assert_eq!(self::issues(&xml, &config), []);
+}
@@ -0,0 +1,135 @@
+use prproj::diff::{diff, Change};
+use prproj::merge::merge;
+use prproj::{PremiereFile, PremiereReader, Reader};
+use std::path::PathBuf;
+
+const SECOND: u64 = 254_016_000_000;
+
+fn xml() -> String {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn read(xml: &[u8]) -> PremiereFile {
+    let mut reader = PremiereReader::new(xml);
+    reader.read().unwrap();
+    reader.take()
+}
+
+/// Values of the attribute `name` throughout the XML.
+fn attrs<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let prefix = format!(" {}=\"", name);
+    xml.split(prefix.as_str())
+        .skip(1)
+        .map(|rest| &rest[..rest.find('"').unwrap()])
+        .collect()
+}
+
+/// Pushes clip 130 on V1 and its audio back by `seconds`.
+fn move_clip(xml: &str, seconds: u64) -> String {
+    xml.replace(
+        "<Start>5715360000000</Start>\n\t\t\t\t<End>8752968000000</End>",
+        &format!(
+            "<Start>{}</Start>\n\t\t\t\t<End>{}</End>",
+            5715360000000 + seconds * SECOND,
+            8752968000000 + seconds * SECOND
+        ),
+    )
+}
+
+/// Adds an empty bin and a copy of "test_supercut" using the same sequence ID.
+fn add_bin_and_sequence(xml: &str) -> String {
+    let start = xml.find("<Sequence ObjectUID=\"dfd47690").unwrap();
+    let end = start + xml[start..].find("</Sequence>").unwrap() + "</Sequence>".len();
+    let copy = xml[start..end]
+        .replace("dfd47690-1a69-4f27-869e-a9796dc93dec", "dfd47690-0000-0000-0000-000000000000")
+        .replace("<Name>test_supercut</Name>", "<Name>test_supercut copy</Name>");
+    let bin = "<BinProjectItem ObjectUID=\"b1b1b1b1-0000-0000-0000-000000000000\" Version=\"1\">\
+        <ProjectItem Version=\"1\"><Name>Selects</Name></ProjectItem>\
+        <ProjectItemContainer Version=\"1\"><Items Version=\"1\"/></ProjectItemContainer>\
+        </BinProjectItem>";
+    let last_item = "<Item Index=\"4\" ObjectURef=\"23f2f44d-2b23-4b76-a623-a839ee3eb891\"/>";
+    xml.replace(
+        last_item,
+        &format!("{}<Item Index=\"5\" ObjectURef=\"b1b1b1b1-0000-0000-0000-000000000000\"/>", last_item),
+    )
+    .replace("</PremiereData>", &format!("{}{}</PremiereData>", copy, bin))
+}
+
+#[test]
+fn it_copies_new_sequences_and_bins() {
+    let base = xml();
+    let theirs = add_bin_and_sequence(&base);
+    let merged = merge(base.as_bytes(), base.as_bytes(), theirs.as_bytes()).unwrap();
+    assert!(merged.conflicts.is_empty());
+    assert!(matches!(&merged.applied[0], Change::SequenceAdded { name, .. } if name == "test_supercut copy"));
+    assert!(matches!(&merged.applied[1], Change::ItemAdded { name, .. } if name == "Selects"));
+
+    let mut xml = Vec::new();
+    merged.write_xml(&mut xml).unwrap();
+    let project = read(&xml);
+    assert_eq!(project.sequences.len(), 3);
+    let copy = project.sequences.iter().find(|seq| seq.name() == "test_supercut copy").unwrap();
+    assert_eq!(copy.id, 3);
+    assert_eq!(copy.cuts().len(), 18);
+    assert!(project.items.iter().any(|item| item.is_bin && item.name == "Selects"));
+    // Our sequences are untouched
+    let ours = read(base.as_bytes());
+    assert_eq!(diff(&ours, &project).len(), 2);
+}
+
+#[test]
+fn it_takes_sequences_only_edited_in_theirs() {
+    let base = xml();
+    let theirs = move_clip(&base, 1);
+    let merged = merge(base.as_bytes(), base.as_bytes(), theirs.as_bytes()).unwrap();
+    assert!(merged.conflicts.is_empty());
+    assert_eq!(merged.applied.len(), 2);
+
+    let mut gzipped = Vec::new();
+    merged.write_gzip(&mut gzipped).unwrap();
+    let project = read(&gzipped);
+    assert_eq!(diff(&read(theirs.as_bytes()), &project), []);
+
+    // Our clips of the sequence are gone, the markers they shared with the master clips are kept
+    let mut xml = Vec::new();
+    merged.write_xml(&mut xml).unwrap();
+    let xml = String::from_utf8(xml).unwrap();
+    let ids = attrs(&xml, "ObjectID");
+    let refs = attrs(&xml, "ObjectRef");
+    assert!(ids.iter().all(|id| refs.contains(id)), "unreferenced objects are left");
+    assert!(refs.iter().all(|id| ids.contains(id)));
+    assert!(ids.contains(&"65"));
+    assert_eq!(attrs(&xml, "ObjectUID").len(), attrs(&theirs, "ObjectUID").len());
+}
+
+#[test]
+fn it_reports_sequences_edited_on_both_sides() {
+    let base = xml();
+    let ours = move_clip(&base, 2);
+    let theirs = move_clip(&base, 1);
+    let merged = merge(base.as_bytes(), ours.as_bytes(), theirs.as_bytes()).unwrap();
+    assert!(merged.applied.is_empty());
+    assert_eq!(merged.conflicts.len(), 1);
+    let conflict = &merged.conflicts[0];
+    assert_eq!(conflict.sequence, "test_supercut");
+    assert_eq!(conflict.ours.len(), 2);
+    assert!(conflict.to_string().starts_with("conflict in sequence \"test_supercut\"\n  ours:   ~"));
+
+    let mut xml = Vec::new();
+    merged.write_xml(&mut xml).unwrap();
+    assert_eq!(diff(&read(ours.as_bytes()), &read(&xml)), []);
+
+    // Editing the same way on both sides isn't a conflict
+    let merged = merge(base.as_bytes(), theirs.as_bytes(), theirs.as_bytes()).unwrap();
+    assert!(merged.conflicts.is_empty() && merged.applied.is_empty());
+}
+
+
+#[test]
+fn it_fails_on_broken_projects() {
+    let base = xml();
+    assert!(merge(base.as_bytes(), b"not a project", base.as_bytes()).is_err());
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "rayon")]
+
+use prproj::{PremiereFile, PremiereReader, Reader};
+use std::path::PathBuf;
+
+fn read(parallel: bool, flatten: bool) -> PremiereFile {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.zipped.prproj"].iter().collect::<PathBuf>());
+    let mut reader = PremiereReader::from_path(&path);
+    reader.set_parallel(parallel);
+    reader.set_flatten_nested(flatten);
+    reader.read().unwrap();
+    reader.take()
+}
+
+#[test]
+fn it_resolves_sequences_like_the_serial_path() {
+    for flatten in [false, true] {
+        let serial = read(false, flatten);
+        let parallel = read(true, flatten);
+        assert_eq!(format!("{:?}", parallel.sequences), format!("{:?}", serial.sequences));
+        assert_eq!(format!("{:?}", parallel.media), format!("{:?}", serial.media));
+    }
+}
+
+#[test]
+fn it_builds_timelines_and_links_in_parallel() {
+    let project = read(true, false);
+    let seq = &project.sequences[1];
+    assert_eq!(seq.timeline().items().len(), 9);
+    assert!(seq.cuts().iter().all(|cut| cut.is_linked()));
+}
@@ -0,0 +1,58 @@
+use prproj::scan::{find_projects, scan};
+use std::path::PathBuf;
+
+fn test_file(name: &str) -> PathBuf {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", name].iter().collect::<PathBuf>());
+    path
+}
+
+/// A directory of its own for every run of the tests, emptied first.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn it_indexes_projects_below_a_directory() {
+    let dir = temp_dir("prproj-scan");
+    std::fs::create_dir_all(dir.join("b").join("nested")).unwrap();
+    std::fs::create_dir_all(dir.join("Adobe Premiere Pro Auto-Save")).unwrap();
+    std::fs::copy(test_file("test.unzipped.prproj"), dir.join("a.prproj")).unwrap();
+    std::fs::copy(test_file("test.zipped.prproj"), dir.join("b").join("nested").join("b.PRPROJ")).unwrap();
+    std::fs::copy(test_file("test.zipped.prproj"), dir.join("Adobe Premiere Pro Auto-Save").join("a-1.prproj")).unwrap();
+    std::fs::write(dir.join("b").join("broken.prproj"), "not a project").unwrap();
+    std::fs::write(dir.join("notes.txt"), "").unwrap();
+
+    assert_eq!(find_projects(&dir).unwrap().len(), 3);
+
+    let projects = scan(&dir).unwrap();
+    let names: Vec<_> = projects.iter().map(|project| project.path.strip_prefix(&dir).unwrap()).collect();
+    assert_eq!(names, [
+        PathBuf::from("a.prproj"),
+        ["b", "broken.prproj"].iter().collect(),
+        ["b", "nested", "b.PRPROJ"].iter().collect(),
+    ]);
+
+    let broken = &projects[1];
+    assert!(broken.error.is_some());
+    assert!(broken.sequences.is_empty());
+
+    for project in [&projects[0], &projects[2]] {
+        assert_eq!(project.error, None);
+        assert_eq!(project.sequences.len(), 2);
+        let supercut = &project.sequences[1];
+        assert_eq!(supercut.name, "test_supercut");
+        assert_eq!(supercut.media.len(), 3);
+        assert!(project.uses(&supercut.media[0]));
+        assert!(project.sequences[0].media.is_empty());
+    }
+    assert!(!projects[0].uses("missing.mp4"));
+}
+
+#[test]
+fn it_fails_on_missing_directories() {
+    assert!(scan(&std::env::temp_dir().join("prproj-scan-missing")).is_err());
+}
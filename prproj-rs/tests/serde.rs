@@ -0,0 +1,46 @@
+#![cfg(feature = "serde")]
+
+use prproj::premiere::CutSource;
+use prproj::{PremiereFile, PremiereReader, Reader};
+use std::path::PathBuf;
+
+fn project() -> PremiereFile {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    let mut reader = PremiereReader::from_path(&path);
+    reader.read().unwrap();
+    reader.take()
+}
+
+#[test]
+fn it_round_trips_through_json() {
+    let project = project();
+    let json = serde_json::to_string(&project).unwrap();
+    let cached: PremiereFile = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&cached).unwrap(), json);
+
+    let seq = cached.sequences.iter().find(|seq| seq.id == 2).unwrap();
+    assert_eq!(seq.name(), "test_supercut");
+    assert_eq!(seq.cuts().len(), 18);
+    assert_eq!(seq.timeline().items().len(), project.sequences[1].timeline().items().len());
+    assert_eq!(seq.settings().fps(), 24.);
+    let cut = seq.cuts().iter().find(|cut| cut.id == 124).unwrap();
+    assert_eq!(cut.linked(), [123]);
+    assert!(matches!(cut.source(), CutSource::Medium(_)));
+}
+
+#[test]
+fn it_uses_documented_names() {
+    let project = project();
+    let json = serde_json::to_value(&project).unwrap();
+    let seq = &json["sequences"][1];
+    assert_eq!(seq["name"], "test_supercut");
+    assert_eq!(seq["settings"]["field_order"], "progressive");
+    assert_eq!(seq["timeline"].as_array().unwrap().len(), 9);
+    let cut = &seq["cuts"][0];
+    assert_eq!(cut["kind"], "audio");
+    let medium = cut["source"]["medium"].as_u64().unwrap() as usize;
+    assert!(json["media"][medium]["file_path"].is_string());
+    assert!(seq.get("links").is_none());
+}
@@ -0,0 +1,67 @@
+use prproj::{PremiereReader, Reader};
+use prproj::premiere::TrackKind;
+use std::path::PathBuf;
+
+/// Makes the video clip of the first cut of "test_supercut" play in reverse at 50%.
+fn project_with_speed_change() -> String {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    let xml = std::fs::read_to_string(path).unwrap();
+
+    let at = xml.find("<VideoClip ObjectID=\"208\"").unwrap();
+    let at = at + xml[at..].find("<InPoint>").unwrap();
+    format!("{}<PlaybackSpeed>-0.5</PlaybackSpeed>{}", &xml[..at], &xml[at..])
+}
+
+#[test]
+fn it_accounts_for_speed_in_source_range() {
+    let mut reader = PremiereReader::new(project_with_speed_change().as_bytes());
+    reader.read().unwrap();
+    let seq = &reader.sequences()[1];
+    let cut = seq
+        .cuts()
+        .iter()
+        .find(|cut| cut.kind == TrackKind::Video && cut.in_point == 17.5)
+        .unwrap();
+
+    assert_eq!(cut.speed().rate, 0.5);
+    assert!(cut.speed().reverse);
+    let (from, to) = cut.source_range();
+    assert_eq!(from, cut.in_point);
+    assert!(((to - from) - (cut.end - cut.start) / 2.).abs() < 1e-9);
+    // Reversed, so the cut starts at the end of its source range
+    assert!((cut.source_time_at(cut.start) - to).abs() < 1e-9);
+    assert!((cut.source_time_at(cut.end) - from).abs() < 1e-9);
+}
+
+#[test]
+fn it_exports_speed_changes_to_otio_as_time_warps() {
+    use prproj::export::otio;
+
+    let mut reader = PremiereReader::new(project_with_speed_change().as_bytes());
+    reader.read().unwrap();
+    let project = reader.take();
+    let timeline = otio::timeline(&project, &project.sequences[1]);
+    let v2 = &timeline["tracks"]["children"][1];
+    assert_eq!(v2["name"], "V2");
+    let clip = &v2["children"][0];
+    assert_eq!(clip["metadata"]["premiere_id"], 124);
+    assert_eq!(
+        clip["effects"],
+        serde_json::json!([{
+            "OTIO_SCHEMA": "LinearTimeWarp.1",
+            "name": "",
+            "effect_name": "LinearTimeWarp",
+            "time_scalar": -0.5,
+            "metadata": {},
+        }])
+    );
+    // The duration on the track, starting at the clip's in point at 24 fps
+    assert_eq!(clip["source_range"]["start_time"]["value"], 17.5 * 24.);
+    assert_eq!(clip["source_range"]["duration"]["value"], 151.);
+
+    // Clips at 100% have no effects
+    let v1 = &timeline["tracks"]["children"][0];
+    assert!(v1["children"].as_array().unwrap().iter().all(|item| item["effects"] == serde_json::json!([])));
+}
@@ -0,0 +1,62 @@
+use prproj::{PremiereReader, Reader};
+use std::path::PathBuf;
+
+const FRAME: u64 = 10_584_000_000;
+
+fn project() -> String {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    std::fs::read_to_string(path).unwrap()
+}
+
+/// Slips the audio linked to the first video clip of "test_supercut" two frames late.
+fn project_with_slipped_audio() -> String {
+    let xml = project();
+    let at = xml.find("<AudioClipTrackItem ObjectID=\"123\"").unwrap();
+    let end = at + xml[at..].find("</AudioClipTrackItem>").unwrap();
+    let item = xml[at..end]
+        .replace("<Start>0</Start>", &format!("<Start>{}</Start>", 2 * FRAME))
+        .replace("<End>1598184000000</End>", &format!("<End>{}</End>", 1_598_184_000_000 + 2 * FRAME));
+    format!("{}{}{}", &xml[..at], item, &xml[end..])
+}
+
+#[test]
+fn it_finds_no_slips_in_synced_sequences() {
+    let mut reader = PremiereReader::new(project().as_bytes());
+    reader.read().unwrap();
+    for seq in reader.sequences() {
+        assert!(seq.out_of_sync().is_empty());
+    }
+}
+
+#[test]
+fn it_reports_slipped_audio_in_frames() {
+    let mut reader = PremiereReader::new(project_with_slipped_audio().as_bytes());
+    reader.read().unwrap();
+    let seq = &reader.sequences()[1];
+    let slips = seq.out_of_sync();
+    assert_eq!(slips.len(), 1);
+
+    let slip = &slips[0];
+    assert_eq!(slip.sequence_name, "test_supercut");
+    assert_eq!((slip.video_cut, slip.audio_cut), (124, 123));
+    assert_eq!(slip.offset, 2);
+    assert_eq!(slip.timecode.to_string(), "00:00:00:00");
+}
+
+#[test]
+fn it_takes_a_speed_of_zero_as_unknown() {
+    // The video clip linked to the slipped audio
+    let xml = project_with_slipped_audio();
+    let at = xml.find("<VideoClip ObjectID=\"208\"").unwrap();
+    let at = at + xml[at..].find("<InPoint>").unwrap();
+    let xml = format!("{}<PlaybackSpeed>0</PlaybackSpeed>{}", &xml[..at], &xml[at..]);
+    let mut reader = PremiereReader::new(xml.as_bytes());
+    reader.read().unwrap();
+    let seq = &reader.sequences()[1];
+    assert_eq!(seq.cuts().iter().find(|cut| cut.id == 124).unwrap().speed().rate, 0.);
+    let slips = seq.out_of_sync();
+    assert_eq!(slips.len(), 1);
+    assert_eq!(slips[0].offset, 2);
+}
@@ -0,0 +1,18 @@
+use prproj::timecode::{self, Timecode};
+
+#[test]
+fn it_formats_non_drop_frame_timecode() {
+    let fps = timecode::fps(10_584_000_000);
+    assert_eq!(fps, 24.);
+    assert_eq!(Timecode::from_seconds(3661.5, fps, false).to_string(), "01:01:01:12");
+}
+
+#[test]
+fn it_skips_frame_numbers_in_drop_frame_timecode() {
+    let fps = 30000. / 1001.;
+    assert_eq!(Timecode::from_frames(1799, fps, true).to_string(), "00:00:59;29");
+    assert_eq!(Timecode::from_frames(1800, fps, true).to_string(), "00:01:00;02");
+    assert_eq!(Timecode::from_frames(17982, fps, true).to_string(), "00:10:00;00");
+    // Only at NTSC rates
+    assert_eq!(Timecode::from_frames(1800, 30., true).to_string(), "00:01:00:00");
+}
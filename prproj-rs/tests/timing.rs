@@ -0,0 +1,53 @@
+use prproj::{PremiereReader, Reader};
+use std::path::PathBuf;
+
+const SECOND: u64 = 254_016_000_000;
+
+fn project() -> String {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    std::fs::read_to_string(path).unwrap()
+}
+
+/// Starts "test_supercut" at 01:00:00:00 and marks its first 10 seconds.
+fn project_with_start_time_and_marks() -> String {
+    let xml = project();
+    // The second sequence
+    let at = xml.rfind("<MZ.WorkInPoint>").unwrap();
+    format!(
+        "{}<MZ.InPoint>0</MZ.InPoint><MZ.OutPoint>{}</MZ.OutPoint><MZ.ZeroPoint>{}</MZ.ZeroPoint>{}",
+        &xml[..at],
+        10 * SECOND,
+        3600 * SECOND,
+        &xml[at..]
+    )
+}
+
+#[test]
+fn it_separates_work_area_from_duration() {
+    let mut reader = PremiereReader::new(project().as_bytes());
+    reader.read().unwrap();
+    let seq = &reader.sequences()[1];
+    assert_eq!(seq.work_area(), (0., 464.125));
+    assert_eq!(seq.work_area_duration().as_secs_f64(), 464.125);
+
+    let last_end = seq.cuts().iter().map(|cut| cut.end).fold(0., f64::max);
+    assert!(last_end > 0.);
+    assert_eq!(seq.end(), last_end);
+    assert_eq!(seq.in_mark(), None);
+    assert_eq!(seq.out_mark(), None);
+    assert_eq!(seq.start_timecode().to_string(), "00:00:00:00");
+}
+
+#[test]
+fn it_reads_start_time_and_marks() {
+    let mut reader = PremiereReader::new(project_with_start_time_and_marks().as_bytes());
+    reader.read().unwrap();
+    let seq = &reader.sequences()[1];
+    assert_eq!(seq.start_time(), 3600.);
+    assert_eq!(seq.start_timecode().to_string(), "01:00:00:00");
+    assert_eq!(seq.timecode_at(1.5).to_string(), "01:00:01:12");
+    assert_eq!(seq.in_mark(), Some(0.));
+    assert_eq!(seq.out_mark(), Some(10.));
+}
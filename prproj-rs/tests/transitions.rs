@@ -0,0 +1,102 @@
+use prproj::{PremiereReader, Reader};
+use prproj::premiere::{TrackKind, TransitionAlignment};
+use std::path::PathBuf;
+
+const SECOND: u64 = 254_016_000_000;
+
+/// The test project has no transitions, so a Cross Dissolve
+/// centered on the first edit of V1 in "test_supercut" is added.
+fn project_with_transition() -> String {
+    let mut path = std::env::current_dir().unwrap();
+    path.pop();
+    path.push(["test_files", "test.unzipped.prproj"].iter().collect::<PathBuf>());
+    let xml = std::fs::read_to_string(path).unwrap();
+
+    let last_clip_of_v1 = "<TrackItem Index=\"7\" ObjectRef=\"140\"/>";
+    let transition_items = "<TransitionItems Version=\"3\">";
+    let at = xml.find(last_clip_of_v1).unwrap();
+    let at = at + xml[at..].find(transition_items).unwrap() + transition_items.len();
+
+    let objects = format!(
+        "<VideoTransitionTrackItem ObjectID=\"9001\">\
+            <TransitionTrackItem Version=\"7\">\
+                <TrackItem Version=\"3\"><Start>{}</Start><End>{}</End></TrackItem>\
+                <TransitionComponent ObjectRef=\"9002\"/>\
+            </TransitionTrackItem>\
+        </VideoTransitionTrackItem>\
+        <VideoTransitionFilter ObjectID=\"9002\">\
+            <MatchName>AE.ADBE Cross Dissolve New</MatchName>\
+        </VideoTransitionFilter>\
+        </PremiereData>",
+        15 * SECOND, 15 * SECOND + SECOND / 2
+    );
+    format!(
+        "{}<TrackItems Version=\"1\"><TrackItem Index=\"0\" ObjectRef=\"9001\"/></TrackItems>{}",
+        &xml[..at],
+        &xml[at..]
+    ).replace("</PremiereData>", &objects)
+}
+
+#[test]
+fn it_reads_transitions() {
+    let mut reader = PremiereReader::new(project_with_transition().as_bytes());
+    reader.read().unwrap();
+    let seq = &reader.sequences()[1];
+    let transitions: Vec<_> = seq.transitions_on(TrackKind::Video, 0).collect();
+    assert_eq!(transitions.len(), 1);
+
+    let transition = transitions[0];
+    assert_eq!(transition.match_name, "AE.ADBE Cross Dissolve New");
+    assert_eq!(transition.duration(), 0.5);
+    assert_eq!(transition.alignment, TransitionAlignment::Center);
+
+    let cuts: Vec<_> = seq.cuts().iter().collect();
+    let from = cuts[transition.from_cut.unwrap()];
+    let to = cuts[transition.to_cut.unwrap()];
+    assert_eq!(from.end, 15.25);
+    assert_eq!(to.start, 15.25);
+}
+
+#[test]
+fn it_exports_transitions_to_otio() {
+    use prproj::export::otio;
+
+    for (match_name, transition_type) in [
+        ("AE.ADBE Cross Dissolve New", "SMPTE_Dissolve"),
+        ("AE.ADBE Dip To Black", "Custom_Transition"),
+    ] {
+        let xml = project_with_transition().replace("AE.ADBE Cross Dissolve New", match_name);
+        let mut reader = PremiereReader::new(xml.as_bytes());
+        reader.read().unwrap();
+        let project = reader.take();
+        let timeline = otio::timeline(&project, &project.sequences[1]);
+        let v1 = timeline["tracks"]["children"][0]["children"].as_array().unwrap();
+        let schemas: Vec<_> = v1[..4].iter().map(|item| item["OTIO_SCHEMA"].as_str().unwrap()).collect();
+        assert_eq!(schemas, ["Gap.1", "Clip.1", "Transition.1", "Clip.1"]);
+
+        let transition = &v1[2];
+        assert_eq!(transition["transition_type"], transition_type);
+        assert_eq!(transition["metadata"]["match_name"], match_name);
+        // Centered on the edit at 15.25s, 6 frames on each side at 24 fps
+        assert_eq!(transition["in_offset"]["value"], 6.);
+        assert_eq!(transition["out_offset"]["value"], 6.);
+    }
+}
+
+#[test]
+fn it_exports_transitions_to_fcp7_xml() {
+    use prproj::export::fcp7;
+
+    for (match_name, effect_id) in [
+        ("AE.ADBE Cross Dissolve New", "Cross Dissolve"),
+        ("AE.ADBE Dip To Black", "Dip to Color Dissolve"),
+        ("AE.ADBE Page Peel", "Cross Dissolve"),
+    ] {
+        let xml = project_with_transition().replace("AE.ADBE Cross Dissolve New", match_name);
+        let mut reader = PremiereReader::new(xml.as_bytes());
+        reader.read().unwrap();
+        let project = reader.take();
+        let xml = fcp7::write(&project, &project.sequences[1]);
+        assert!(xml.contains(&format!("<effectid>{}</effectid>", effect_id)), "{}", match_name);
+    }
+}
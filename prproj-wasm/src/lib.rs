@@ -1,5 +1,6 @@
 #![cfg(target_arch = "wasm32")]
 
+use prproj::decompress::Callback;
 use prproj::{
 	PremiereReader as PremiereReaderOriginal,
 	PremiereMedia, PremiereMedium, PremiereSequence,
@@ -8,7 +9,7 @@ use prproj::{
 };
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsValue;
-use js_sys::{Reflect, Array, Object};
+use js_sys::{Reflect, Array, Object, Function, Uint8Array};
 
 impl std::convert::From<PremiereFileOriginal> for PremiereFile {
 	fn from(original: PremiereFileOriginal) -> Self {
@@ -69,6 +70,28 @@ pub fn read_prproj(xml: &[u8]) -> Result<PremiereFile, JsValue> {
 	Ok(reader.take().into())
 }
 
+/// Like `read_prproj`, but gzipped projects are inflated by `inflate`,
+/// a JavaScript function taking and returning a `Uint8Array`
+/// (e.g. `fflate.gunzipSync`). Projects already decompressed with
+/// `DecompressionStream` can be passed to `read_prproj` as they are.
+#[wasm_bindgen]
+pub fn read_prproj_with_inflate(xml: &[u8], inflate: &Function) -> Result<PremiereFile, JsValue> {
+	let callback = Callback(|gzip: &[u8]| {
+		inflate
+			.call1(&JsValue::NULL, &Uint8Array::from(gzip))
+			.map(|inflated| Uint8Array::new(&inflated).to_vec())
+			.map_err(|err| format!("{:?}", err))
+	});
+	let mut reader = PremiereReaderOriginal::with_decompressor(xml, &callback)
+		.map_err(|err| JsValue::from_str(&err.to_string()))?;
+	reader.read().map_err(|err|
+		JsValue::from_str(
+			&format!("{:?}", err)
+		)
+	)?;
+	Ok(reader.take().into())
+}
+
 // #[cfg(not(target_arch = "wasm32"))]
 // #[cfg(test)]
 // mod tests {